@@ -0,0 +1,126 @@
+use std::fmt::{Debug, Formatter};
+
+/// A set of bytes. Used to declare the possible first bytes of a rule's matches.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ByteSet {
+    bits: [u64; 4],
+}
+
+impl ByteSet {
+    //! Constants
+
+    /// The empty set.
+    pub const EMPTY: Self = Self { bits: [0; 4] };
+
+    /// The set of all bytes.
+    pub const ALL: Self = Self {
+        bits: [u64::MAX; 4],
+    };
+
+    /// The ASCII digits: `[0-9]`.
+    pub const ASCII_DIGIT: Self = Self::EMPTY.with_range(b'0', b'9');
+
+    /// The ASCII identifier start bytes: `[a-zA-Z_]`.
+    pub const IDENT_START: Self = Self::EMPTY
+        .with_range(b'a', b'z')
+        .with_range(b'A', b'Z')
+        .with_byte(b'_');
+
+    /// The ASCII whitespace bytes: spaces, tabs, newlines, carriage returns, and form feeds.
+    pub const ASCII_WHITESPACE: Self = Self::EMPTY
+        .with_byte(b' ')
+        .with_byte(b'\t')
+        .with_byte(b'\n')
+        .with_byte(b'\r')
+        .with_byte(b'\x0C');
+}
+
+impl ByteSet {
+    //! Construction
+
+    /// Adds the `byte`. (builder pattern)
+    #[must_use]
+    pub const fn with_byte(mut self, byte: u8) -> Self {
+        self.bits[(byte / 64) as usize] |= 1 << (byte % 64);
+        self
+    }
+
+    /// Adds the bytes from `first` to `last` inclusive. (builder pattern)
+    #[must_use]
+    pub const fn with_range(mut self, first: u8, last: u8) -> Self {
+        let mut byte: u8 = first;
+        while byte <= last {
+            self = self.with_byte(byte);
+            if byte == u8::MAX {
+                break;
+            }
+            byte += 1;
+        }
+        self
+    }
+
+    /// Adds the bytes of the `other` set. (builder pattern)
+    #[must_use]
+    pub const fn with_set(mut self, other: Self) -> Self {
+        let mut i: usize = 0;
+        while i < 4 {
+            self.bits[i] |= other.bits[i];
+            i += 1;
+        }
+        self
+    }
+}
+
+impl ByteSet {
+    //! Properties
+
+    /// Checks if the set contains the `byte`.
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.bits[(byte / 64) as usize] & (1 << (byte % 64)) != 0
+    }
+
+    /// Checks if the set is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.bits[0] == 0 && self.bits[1] == 0 && self.bits[2] == 0 && self.bits[3] == 0
+    }
+
+    /// Gets an iterator over the bytes in the set in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..=u8::MAX).filter(|byte| self.contains(*byte))
+    }
+}
+
+impl Debug for ByteSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set()
+            .entries(self.iter().map(|byte| byte.escape_ascii().to_string()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains() {
+        let set: ByteSet = ByteSet::IDENT_START;
+        assert!(set.contains(b'a'));
+        assert!(set.contains(b'Z'));
+        assert!(set.contains(b'_'));
+        assert!(!set.contains(b'0'));
+        assert!(!set.contains(0xFF));
+
+        assert!(ByteSet::ALL.contains(0));
+        assert!(ByteSet::ALL.contains(0xFF));
+        assert!(ByteSet::EMPTY.is_empty());
+        assert!(ByteSet::EMPTY.with_range(0xF0, 0xFF).contains(0xFF));
+    }
+
+    #[test]
+    fn iter() {
+        let set: ByteSet = ByteSet::ASCII_DIGIT.with_set(ByteSet::EMPTY.with_byte(b'-'));
+        let bytes: Vec<u8> = set.iter().collect();
+        assert_eq!(bytes, b"-0123456789");
+    }
+}
@@ -0,0 +1,15 @@
+use crate::lexer::{Interner, Value};
+
+/// A token value decoder. Decodes the text of a matched token into a [Value].
+///
+/// Implemented for fn pointers and closures taking the token text and the [Interner].
+pub trait Decoder: Send + Sync {
+    /// Decodes the token `text`. Returns `None` if the text is not a valid value.
+    fn decode(&self, text: &str, interner: &mut Interner) -> Option<Value>;
+}
+
+impl<F: Fn(&str, &mut Interner) -> Option<Value> + Send + Sync> Decoder for F {
+    fn decode(&self, text: &str, interner: &mut Interner) -> Option<Value> {
+        self(text, interner)
+    }
+}
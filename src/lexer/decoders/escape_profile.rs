@@ -0,0 +1,88 @@
+/// The escape sequences a language supports in strings. Used by [unescape].
+///
+/// The simple escapes `\n`, `\r`, `\t`, `\0`, `\\`, `\'` and `\"` are always supported.
+///
+/// [unescape]: crate::lexer::decoders::unescape
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct EscapeProfile {
+    unicode: bool,
+    hex: bool,
+    ascii_hex: bool,
+    octal: bool,
+}
+
+impl EscapeProfile {
+    //! Profiles
+
+    /// Rust-style escapes: the simple escapes, ASCII `\xNN` (up to `\x7F`) and `\u{...}`.
+    pub const RUST: Self = Self {
+        unicode: true,
+        hex: true,
+        ascii_hex: true,
+        octal: false,
+    };
+
+    /// C-style escapes: the simple escapes, `\xNN` and octal `\NNN`.
+    pub const C: Self = Self {
+        unicode: false,
+        hex: true,
+        ascii_hex: false,
+        octal: true,
+    };
+}
+
+impl EscapeProfile {
+    //! Builders
+
+    /// Enables `\u{...}` escapes with 1 to 6 hex digits. (builder pattern)
+    #[must_use]
+    pub const fn with_unicode(mut self) -> Self {
+        self.unicode = true;
+        self
+    }
+
+    /// Enables `\xNN` escapes with exactly 2 hex digits. (builder pattern)
+    #[must_use]
+    pub const fn with_hex(mut self) -> Self {
+        self.hex = true;
+        self
+    }
+
+    /// Limits `\xNN` escapes to ASCII, up to `\x7F`. (builder pattern)
+    #[must_use]
+    pub const fn with_ascii_hex(mut self) -> Self {
+        self.ascii_hex = true;
+        self
+    }
+
+    /// Enables octal `\NNN` escapes with 1 to 3 octal digits, up to `\377`. (builder pattern)
+    #[must_use]
+    pub const fn with_octal(mut self) -> Self {
+        self.octal = true;
+        self
+    }
+}
+
+impl EscapeProfile {
+    //! Properties
+
+    /// Checks if `\u{...}` escapes are supported.
+    pub const fn unicode(&self) -> bool {
+        self.unicode
+    }
+
+    /// Checks if `\xNN` escapes are supported.
+    pub const fn hex(&self) -> bool {
+        self.hex
+    }
+
+    /// Checks if `\xNN` escapes are limited to ASCII.
+    pub const fn ascii_hex(&self) -> bool {
+        self.ascii_hex
+    }
+
+    /// Checks if octal `\NNN` escapes are supported.
+    pub const fn octal(&self) -> bool {
+        self.octal
+    }
+}
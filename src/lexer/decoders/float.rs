@@ -0,0 +1,34 @@
+use crate::lexer::decoders::parse_float;
+use crate::lexer::{Interner, Value};
+
+/// Decodes a floating-point number with optional `_` separators and suffix. (e.g. `3.5e2`, see
+/// [parse_float])
+///
+/// Returns `None` for malformed numbers.
+pub fn float(text: &str, _interner: &mut Interner) -> Option<Value> {
+    parse_float(text).ok().map(Value::Float)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_float() {
+        let test_cases: &[(&str, Option<f64>)] = &[
+            ("", None),
+            ("abc", None),
+            ("inf", None),
+            ("3.5", Some(3.5)),
+            ("3.5e2", Some(350.0)),
+            ("1_000.5", Some(1000.5)),
+            ("42", Some(42.0)),
+        ];
+
+        let mut interner: Interner = Interner::default();
+        for (text, expected) in test_cases {
+            let value: Option<Value> = float(text, &mut interner);
+            assert_eq!(value, expected.map(Value::Float), "text: {:?}", text);
+        }
+    }
+}
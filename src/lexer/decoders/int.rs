@@ -0,0 +1,39 @@
+use crate::lexer::decoders::parse_int;
+use crate::lexer::{Interner, Value};
+
+/// Decodes an unsigned integer: decimal, or `0x`, `0o` or `0b` prefixed, with optional `_`
+/// separators and suffix. (see [parse_int])
+///
+/// Returns `None` for malformed or overflowing integers.
+pub fn int(text: &str, _interner: &mut Interner) -> Option<Value> {
+    parse_int(text).ok().map(Value::Int)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_int() {
+        let test_cases: &[(&str, Option<u128>)] = &[
+            ("", None),
+            ("abc", None),
+            ("0", Some(0)),
+            ("123", Some(123)),
+            ("1_000", Some(1000)),
+            ("0x1F", Some(31)),
+            ("0o17", Some(15)),
+            ("0b1010", Some(10)),
+            ("0x", None),
+            ("+1", None),
+            ("0x+1", None),
+            ("340282366920938463463374607431768211456", None),
+        ];
+
+        let mut interner: Interner = Interner::default();
+        for (text, expected) in test_cases {
+            let value: Option<Value> = int(text, &mut interner);
+            assert_eq!(value, expected.map(Value::Int), "text: {:?}", text);
+        }
+    }
+}
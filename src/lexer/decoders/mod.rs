@@ -0,0 +1,17 @@
+pub use escape_profile::*;
+pub use float::*;
+pub use int::*;
+pub use number_error::*;
+pub use parse_number::*;
+pub use string::*;
+pub use symbol::*;
+pub use unescape::*;
+
+mod escape_profile;
+mod float;
+mod int;
+mod number_error;
+mod parse_number;
+mod string;
+mod symbol;
+mod unescape;
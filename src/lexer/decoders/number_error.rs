@@ -0,0 +1,22 @@
+use std::fmt::{Display, Formatter};
+
+/// A number literal decoding error.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum NumberError {
+    /// The text is not a valid number literal.
+    Malformed,
+
+    /// The number does not fit the target type.
+    Overflow,
+}
+
+impl Display for NumberError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "malformed number literal"),
+            Self::Overflow => write!(f, "number literal out of range"),
+        }
+    }
+}
+
+impl std::error::Error for NumberError {}
@@ -0,0 +1,347 @@
+use crate::lexer::decoders::NumberError;
+
+/// The integer suffixes accepted by [parse_int]. The value must fit the suffix type.
+pub const INT_SUFFIXES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// The float suffixes accepted by [parse_float]. The value must fit the suffix type.
+pub const FLOAT_SUFFIXES: &[&str] = &["f32", "f64"];
+
+/// Parses an unsigned integer literal: decimal, or `0x`, `0o` or `0b` prefixed, with optional `_`
+/// separators between digits and an optional suffix from [INT_SUFFIXES].
+///
+/// A single `_` may separate the digits from the suffix. (e.g. `42_u32`)
+pub fn parse_int(text: &str) -> Result<u128, NumberError> {
+    parse_int_with(text, INT_SUFFIXES)
+}
+
+/// Parses an unsigned integer literal like [parse_int], accepting the `suffixes` instead.
+///
+/// Rust integer type suffixes (e.g. `u8`) are range-checked, other suffixes are not.
+pub fn parse_int_with(text: &str, suffixes: &[impl AsRef<str>]) -> Result<u128, NumberError> {
+    let (value, suffix): (u128, &str) = parse_magnitude(text, suffixes)?;
+    match int_range(suffix) {
+        Some((_, max)) if value > max => Err(NumberError::Overflow),
+        _ => Ok(value),
+    }
+}
+
+/// Parses a signed integer literal: an optional `+` or `-` sign followed by an integer literal
+/// accepted by [parse_int].
+pub fn parse_signed(text: &str) -> Result<i128, NumberError> {
+    parse_signed_with(text, INT_SUFFIXES)
+}
+
+/// Parses a signed integer literal like [parse_signed], accepting the `suffixes` instead.
+///
+/// Rust integer type suffixes (e.g. `i8`) are range-checked, other suffixes are not.
+pub fn parse_signed_with(text: &str, suffixes: &[impl AsRef<str>]) -> Result<i128, NumberError> {
+    let (negative, text): (bool, &str) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let (magnitude, suffix): (u128, &str) = parse_magnitude(text, suffixes)?;
+    let value: i128 = if negative {
+        0i128
+            .checked_sub_unsigned(magnitude)
+            .ok_or(NumberError::Overflow)?
+    } else {
+        i128::try_from(magnitude).map_err(|_| NumberError::Overflow)?
+    };
+    match int_range(suffix) {
+        Some((min, max)) if value < min || (value > 0 && value as u128 > max) => {
+            Err(NumberError::Overflow)
+        }
+        _ => Ok(value),
+    }
+}
+
+/// Parses a float literal: decimal digits with an optional fraction and exponent, optional `_`
+/// separators between digits and an optional suffix from [FLOAT_SUFFIXES]. The fraction may be
+/// empty at the end of the literal. (e.g. `1.`) Prefixed integer literals are converted.
+pub fn parse_float(text: &str) -> Result<f64, NumberError> {
+    parse_float_with(text, FLOAT_SUFFIXES)
+}
+
+/// Parses a float literal like [parse_float], accepting the `suffixes` instead. Prefixed integer
+/// literals accept the same suffixes.
+///
+/// The `f32` suffix is range-checked, other suffixes are not.
+pub fn parse_float_with(text: &str, suffixes: &[impl AsRef<str>]) -> Result<f64, NumberError> {
+    if matches!(text.get(..2), Some("0x" | "0X" | "0o" | "0O" | "0b" | "0B")) {
+        return parse_int_with(text, suffixes).map(|value| value as f64);
+    }
+
+    let bytes: &[u8] = text.as_bytes();
+    let digits = |start: usize| -> usize {
+        start
+            + bytes[start..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit() || **b == b'_')
+                .count()
+    };
+    let mut parts: Vec<&str> = Vec::default();
+    let mut end: usize = digits(0);
+    parts.push(&text[..end]);
+    if bytes.get(end) == Some(&b'.') && bytes.get(end + 1).is_some_and(u8::is_ascii_digit) {
+        let start: usize = end + 1;
+        end = digits(start);
+        parts.push(&text[start..end]);
+    } else if bytes.get(end) == Some(&b'.') && end + 1 == bytes.len() {
+        if !is_separated(&text[..end], 10, false) {
+            return Err(NumberError::Malformed);
+        }
+        return parse_decimal(&text[..end]);
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign: usize = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        let start: usize = end + 1 + sign;
+        if bytes.get(start).is_some_and(u8::is_ascii_digit) {
+            end = digits(start);
+            parts.push(&text[start..end]);
+        }
+    }
+    let suffix: &str = &text[end..];
+    let last: usize = parts.len() - 1;
+    if !is_suffix(suffix, suffixes)
+        || !parts
+            .iter()
+            .enumerate()
+            .all(|(i, part)| is_separated(part, 10, i == last && !suffix.is_empty()))
+    {
+        return Err(NumberError::Malformed);
+    }
+
+    let value: f64 = parse_decimal(&text[..end])?;
+    if suffix == "f32" && !(value as f32).is_finite() {
+        Err(NumberError::Overflow)
+    } else {
+        Ok(value)
+    }
+}
+
+/// Parses the integer digits and suffix of an unsigned integer literal. Returns the value and the
+/// suffix without separators.
+fn parse_magnitude<'a>(
+    text: &'a str,
+    suffixes: &[impl AsRef<str>],
+) -> Result<(u128, &'a str), NumberError> {
+    let (radix, digits): (u32, &str) = match text.get(..2) {
+        Some("0x" | "0X") => (16, &text[2..]),
+        Some("0o" | "0O") => (8, &text[2..]),
+        Some("0b" | "0B") => (2, &text[2..]),
+        _ => (10, text),
+    };
+    let end: usize = digits
+        .find(|c: char| !c.is_digit(radix) && c != '_')
+        .unwrap_or(digits.len());
+    let (digits, suffix): (&str, &str) = digits.split_at(end);
+    if !is_suffix(suffix, suffixes) || !is_separated(digits, radix, !suffix.is_empty()) {
+        return Err(NumberError::Malformed);
+    }
+
+    let mut value: u128 = 0;
+    for digit in digits.chars().filter_map(|c| c.to_digit(radix)) {
+        value = value
+            .checked_mul(radix as u128)
+            .and_then(|value| value.checked_add(digit as u128))
+            .ok_or(NumberError::Overflow)?;
+    }
+    Ok((value, suffix))
+}
+
+/// Parses validated decimal float `text`, ignoring `_` separators.
+fn parse_decimal(text: &str) -> Result<f64, NumberError> {
+    let number: String = text.chars().filter(|c| *c != '_').collect();
+    let value: f64 = number.parse().map_err(|_| NumberError::Malformed)?;
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(NumberError::Overflow)
+    }
+}
+
+/// Gets the range of the Rust integer type named by the `suffix`, if any.
+fn int_range(suffix: &str) -> Option<(i128, u128)> {
+    Some(match suffix {
+        "u8" => (0, u8::MAX as u128),
+        "u16" => (0, u16::MAX as u128),
+        "u32" => (0, u32::MAX as u128),
+        "u64" => (0, u64::MAX as u128),
+        "u128" => (0, u128::MAX),
+        "usize" => (0, usize::MAX as u128),
+        "i8" => (i8::MIN as i128, i8::MAX as u128),
+        "i16" => (i16::MIN as i128, i16::MAX as u128),
+        "i32" => (i32::MIN as i128, i32::MAX as u128),
+        "i64" => (i64::MIN as i128, i64::MAX as u128),
+        "i128" => (i128::MIN, i128::MAX as u128),
+        "isize" => (isize::MIN as i128, isize::MAX as u128),
+        _ => return None,
+    })
+}
+
+/// Checks if the `text` is empty or one of the `suffixes`.
+fn is_suffix(text: &str, suffixes: &[impl AsRef<str>]) -> bool {
+    text.is_empty() || suffixes.iter().any(|suffix| suffix.as_ref() == text)
+}
+
+/// Checks if the `digits` start with a digit in the `radix` and only have single `_` separators
+/// between digits. A trailing `_` is allowed only `before_suffix`.
+fn is_separated(digits: &str, radix: u32, before_suffix: bool) -> bool {
+    let digits: &str = match digits.strip_suffix('_') {
+        Some(digits) if before_suffix => digits,
+        _ => digits,
+    };
+    digits.starts_with(|c: char| c.is_digit(radix))
+        && !digits.ends_with('_')
+        && !digits.contains("__")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_parse_int() {
+        let test_cases: &[(&str, Result<u128, NumberError>)] = &[
+            ("", Err(NumberError::Malformed)),
+            ("abc", Err(NumberError::Malformed)),
+            ("_1", Err(NumberError::Malformed)),
+            ("0x", Err(NumberError::Malformed)),
+            ("0x_", Err(NumberError::Malformed)),
+            ("+1", Err(NumberError::Malformed)),
+            ("1.5", Err(NumberError::Malformed)),
+            ("1u8!", Err(NumberError::Malformed)),
+            ("0b102", Err(NumberError::Malformed)),
+            ("0", Ok(0)),
+            ("1_000", Ok(1000)),
+            ("1_000_", Err(NumberError::Malformed)),
+            ("1_", Err(NumberError::Malformed)),
+            ("1__0", Err(NumberError::Malformed)),
+            ("1e5", Err(NumberError::Malformed)),
+            ("12abc", Err(NumberError::Malformed)),
+            ("0xFFG", Err(NumberError::Malformed)),
+            ("42u7", Err(NumberError::Malformed)),
+            ("42__u32", Err(NumberError::Malformed)),
+            ("0x1F", Ok(31)),
+            ("0xFFu8", Ok(255)),
+            ("0o17", Ok(15)),
+            ("0b1010", Ok(10)),
+            ("42u32", Ok(42)),
+            ("42_i64", Ok(42)),
+            ("255u8", Ok(255)),
+            ("300u8", Err(NumberError::Overflow)),
+            ("127i8", Ok(127)),
+            ("128i8", Err(NumberError::Overflow)),
+            ("0x1_0000_0000u32", Err(NumberError::Overflow)),
+            ("340282366920938463463374607431768211455", Ok(u128::MAX)),
+            (
+                "340282366920938463463374607431768211456",
+                Err(NumberError::Overflow),
+            ),
+        ];
+
+        for (text, expected) in test_cases {
+            assert_eq!(parse_int(text), *expected, "text: {:?}", text);
+        }
+    }
+
+    #[test]
+    fn fn_parse_signed() {
+        let test_cases: &[(&str, Result<i128, NumberError>)] = &[
+            ("", Err(NumberError::Malformed)),
+            ("-", Err(NumberError::Malformed)),
+            ("42", Ok(42)),
+            ("+42", Ok(42)),
+            ("-0x10", Ok(-16)),
+            ("-170141183460469231731687303715884105728", Ok(i128::MIN)),
+            ("170141183460469231731687303715884105727", Ok(i128::MAX)),
+            ("-128i8", Ok(-128)),
+            ("-129i8", Err(NumberError::Overflow)),
+            ("128i8", Err(NumberError::Overflow)),
+            ("-1u8", Err(NumberError::Overflow)),
+            ("-0u8", Ok(0)),
+            (
+                "170141183460469231731687303715884105728",
+                Err(NumberError::Overflow),
+            ),
+            (
+                "-170141183460469231731687303715884105729",
+                Err(NumberError::Overflow),
+            ),
+        ];
+
+        for (text, expected) in test_cases {
+            assert_eq!(parse_signed(text), *expected, "text: {:?}", text);
+        }
+    }
+
+    #[test]
+    fn fn_parse_float() {
+        let test_cases: &[(&str, Result<f64, NumberError>)] = &[
+            ("", Err(NumberError::Malformed)),
+            ("abc", Err(NumberError::Malformed)),
+            ("inf", Err(NumberError::Malformed)),
+            (".5", Err(NumberError::Malformed)),
+            ("1.", Ok(1.0)),
+            ("1_000.", Ok(1000.0)),
+            ("1_.", Err(NumberError::Malformed)),
+            ("1.e5", Err(NumberError::Malformed)),
+            ("1.f32", Err(NumberError::Malformed)),
+            ("1e", Err(NumberError::Malformed)),
+            ("1.5.2", Err(NumberError::Malformed)),
+            ("1_.5", Err(NumberError::Malformed)),
+            ("1.5_", Err(NumberError::Malformed)),
+            ("1.5e_2", Err(NumberError::Malformed)),
+            ("2.5abc", Err(NumberError::Malformed)),
+            ("2u8", Err(NumberError::Malformed)),
+            ("42", Ok(42.0)),
+            ("3.5", Ok(3.5)),
+            ("3.5e2", Ok(350.0)),
+            ("1E-2", Ok(0.01)),
+            ("1_000.5", Ok(1000.5)),
+            ("2.5f64", Ok(2.5)),
+            ("2.5_f32", Ok(2.5)),
+            ("0x10", Ok(16.0)),
+            ("1e400", Err(NumberError::Overflow)),
+            ("1e39f32", Err(NumberError::Overflow)),
+            ("1e39f64", Ok(1e39)),
+        ];
+
+        for (text, expected) in test_cases {
+            assert_eq!(parse_float(text), *expected, "text: {:?}", text);
+        }
+    }
+
+    #[test]
+    fn fn_parse_with_suffixes() {
+        let suffixes: &[&str] = &["L", "f"];
+
+        let test_cases: &[(&str, Result<u128, NumberError>)] = &[
+            ("42L", Ok(42)),
+            ("0xFFL", Ok(255)),
+            ("42_L", Ok(42)),
+            ("42u8", Err(NumberError::Malformed)),
+            ("42LL", Err(NumberError::Malformed)),
+        ];
+        for (text, expected) in test_cases {
+            assert_eq!(
+                parse_int_with(text, suffixes),
+                *expected,
+                "text: {:?}",
+                text
+            );
+        }
+
+        assert_eq!(parse_signed_with("-42L", suffixes), Ok(-42));
+        assert_eq!(parse_float_with("1.5f", suffixes), Ok(1.5));
+        assert_eq!(parse_float_with("1.", suffixes), Ok(1.0));
+        assert_eq!(parse_float_with("0x10L", suffixes), Ok(16.0));
+        assert_eq!(
+            parse_float_with("1.5f32", suffixes),
+            Err(NumberError::Malformed)
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use crate::lexer::decoders::{EscapeProfile, unescape};
+use crate::lexer::{Interner, Span, Value};
+
+/// Decodes a string quoted with `"` or `'`. Unescapes `\n`, `\r`, `\t`, `\0`, `\\`, `\'` and
+/// `\"`.
+///
+/// Returns `None` if the text is not quoted or contains an unknown escape.
+pub fn string(text: &str, _interner: &mut Interner) -> Option<Value> {
+    let quote: char = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let body: &str = text.get(1..text.len() - 1)?;
+    if !text.ends_with(quote) {
+        return None;
+    }
+
+    let span: Span = Span::new(0, body.len() as u32);
+    let value: String = unescape(body, span, EscapeProfile::default()).ok()?;
+    Some(Value::Str(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_string() {
+        let test_cases: &[(&str, Option<&str>)] = &[
+            ("", None),
+            ("\"", None),
+            ("abc", None),
+            ("\"abc'", None),
+            ("\"\"", Some("")),
+            ("\"abc\"", Some("abc")),
+            ("'abc'", Some("abc")),
+            (r#""a\nb""#, Some("a\nb")),
+            (r#""a\"b""#, Some("a\"b")),
+            (r#""a\\""#, Some("a\\")),
+            (r#""a\q""#, None),
+        ];
+
+        let mut interner: Interner = Interner::default();
+        for (text, expected) in test_cases {
+            let value: Option<Value> = string(text, &mut interner);
+            let expected: Option<Value> = expected.map(|s| Value::Str(s.to_string()));
+            assert_eq!(value, expected, "text: {:?}", text);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::lexer::{Interner, Value};
+
+/// Decodes the text as an interned symbol.
+pub fn symbol(text: &str, interner: &mut Interner) -> Option<Value> {
+    Some(Value::Symbol(interner.intern(text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_symbol() {
+        let mut interner: Interner = Interner::default();
+        let a: Option<Value> = symbol("foo", &mut interner);
+        let b: Option<Value> = symbol("foo", &mut interner);
+        assert_eq!(a, b);
+        assert_eq!(interner.resolve(a.unwrap().as_symbol().unwrap()), "foo");
+    }
+}
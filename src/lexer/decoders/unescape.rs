@@ -0,0 +1,219 @@
+use crate::lexer::Span;
+use crate::lexer::decoders::EscapeProfile;
+use crate::parser::ParseError;
+
+/// Unescapes the `text` of a string at the `span` of the source, with the escapes of the
+/// `profile`.
+///
+/// The `text` should be the string body without its quotes, e.g. the body of a [StringMatch].
+/// Returns an error for each invalid escape, spanning the exact escape sequence in the source.
+///
+/// # Example
+/// ```
+/// use lex::lexer::Span;
+/// use lex::lexer::decoders::{EscapeProfile, unescape};
+///
+/// let value = unescape(r"a\tb\u{e9}", Span::new(1, 10), EscapeProfile::RUST);
+/// assert_eq!(value.unwrap(), "a\tb\u{e9}");
+///
+/// let errors = unescape(r"a\qb", Span::new(1, 4), EscapeProfile::RUST).unwrap_err();
+/// assert_eq!(errors[0].span(), Span::new(2, 2));
+/// assert_eq!(errors[0].message(), r"unknown escape '\q'");
+/// ```
+///
+/// [StringMatch]: crate::lexer::matchers::StringMatch
+pub fn unescape(text: &str, span: Span, profile: EscapeProfile) -> Result<String, Vec<ParseError>> {
+    debug_assert_eq!(text.len(), span.len() as usize);
+
+    let mut value: String = String::with_capacity(text.len());
+    let mut errors: Vec<ParseError> = Vec::default();
+    let mut pos: usize = 0;
+    while let Some(c) = text[pos..].chars().next() {
+        if c != '\\' {
+            value.push(c);
+            pos += c.len_utf8();
+            continue;
+        }
+        let (result, len): (Result<char, String>, usize) = escape(&text[pos..], profile);
+        match result {
+            Ok(c) => value.push(c),
+            Err(message) => {
+                let offset: u32 = span.offset() + pos as u32;
+                errors.push(ParseError::new(Span::new(offset, len as u32), message));
+            }
+        }
+        pos += len;
+    }
+
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Decodes the escape sequence at the start of the `text`. Returns the decoded char or an error
+/// message, and the length of the sequence.
+fn escape(text: &str, profile: EscapeProfile) -> (Result<char, String>, usize) {
+    let c: char = match text[1..].chars().next() {
+        Some(c) => c,
+        None => return (Err("incomplete escape".to_string()), 1),
+    };
+    match c {
+        'n' => (Ok('\n'), 2),
+        'r' => (Ok('\r'), 2),
+        't' => (Ok('\t'), 2),
+        '\\' | '\'' | '"' => (Ok(c), 2),
+        '0'..='7' if profile.octal() => octal(text),
+        '0' => (Ok('\0'), 2),
+        'x' if profile.hex() => hex(text, profile.ascii_hex()),
+        'u' if profile.unicode() => unicode(text),
+        _ => {
+            let len: usize = 1 + c.len_utf8();
+            (Err(format!("unknown escape '{}'", &text[..len])), len)
+        }
+    }
+}
+
+/// Decodes an octal escape: `\NNN` with 1 to 3 octal digits.
+fn octal(text: &str) -> (Result<char, String>, usize) {
+    let digits: usize = text[1..]
+        .bytes()
+        .take(3)
+        .take_while(|b| (b'0'..=b'7').contains(b))
+        .count();
+    let len: usize = 1 + digits;
+    match u8::from_str_radix(&text[1..len], 8) {
+        Ok(value) => (Ok(char::from(value)), len),
+        Err(_) => (Err("octal escape out of range".to_string()), len),
+    }
+}
+
+/// Decodes a hex escape: `\xNN` with exactly 2 hex digits, up to `\x7F` if `ascii`.
+fn hex(text: &str, ascii: bool) -> (Result<char, String>, usize) {
+    let digits: usize = text[2..]
+        .bytes()
+        .take(2)
+        .take_while(u8::is_ascii_hexdigit)
+        .count();
+    let len: usize = 2 + digits;
+    if digits < 2 {
+        return (Err("hex escape needs 2 hex digits".to_string()), len);
+    }
+    let value: u8 = u8::from_str_radix(&text[2..len], 16).unwrap();
+    if ascii && !value.is_ascii() {
+        return (Err("hex escape out of range".to_string()), len);
+    }
+    (Ok(char::from(value)), len)
+}
+
+/// Decodes a unicode escape: `\u{...}` with 1 to 6 hex digits.
+fn unicode(text: &str) -> (Result<char, String>, usize) {
+    if !text[2..].starts_with('{') {
+        return (Err("expected '{' after '\\u'".to_string()), 2);
+    }
+    let digits: usize = text[3..].bytes().take_while(u8::is_ascii_hexdigit).count();
+    let end: usize = 3 + digits;
+    if !text[end..].starts_with('}') {
+        return (Err("unterminated unicode escape".to_string()), end);
+    }
+    let len: usize = end + 1;
+    if digits == 0 {
+        return (Err("empty unicode escape".to_string()), len);
+    }
+    if digits > 6 {
+        return (
+            Err("unicode escape has more than 6 hex digits".to_string()),
+            len,
+        );
+    }
+    let value: u32 = u32::from_str_radix(&text[3..end], 16).unwrap();
+    match char::from_u32(value) {
+        Some(c) => (Ok(c), len),
+        None => (Err(format!("invalid unicode code point {value:#X}")), len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The expected value or errors as `(offset, len, message)`.
+    type Expected<'a> = Result<&'a str, &'a [(u32, u32, &'a str)]>;
+
+    #[test]
+    fn fn_unescape() {
+        let rust: EscapeProfile = EscapeProfile::RUST;
+        let c: EscapeProfile = EscapeProfile::C;
+        let simple: EscapeProfile = EscapeProfile::default();
+
+        let test_cases: &[(EscapeProfile, &str, Expected)] = &[
+            (simple, "", Ok("")),
+            (simple, "abc é", Ok("abc é")),
+            (simple, r#"\n\r\t\0\\\'\""#, Ok("\n\r\t\0\\'\"")),
+            (simple, r"a\qb", Err(&[(11, 2, r"unknown escape '\q'")])),
+            (simple, r"\é", Err(&[(10, 3, r"unknown escape '\é'")])),
+            (simple, r"a\", Err(&[(11, 1, "incomplete escape")])),
+            (simple, r"\x41", Err(&[(10, 2, r"unknown escape '\x'")])),
+            (rust, r"\x41\x7e", Ok("A~")),
+            (rust, r"\x80", Err(&[(10, 4, "hex escape out of range")])),
+            (c, r"\x80\xFF", Ok("\u{80}\u{FF}")),
+            (
+                rust,
+                r"\x4",
+                Err(&[(10, 3, "hex escape needs 2 hex digits")]),
+            ),
+            (
+                rust,
+                r"\xg1",
+                Err(&[(10, 2, "hex escape needs 2 hex digits")]),
+            ),
+            (rust, r"\u{41}\u{1F600}", Ok("A\u{1F600}")),
+            (rust, r"\u41", Err(&[(10, 2, r"expected '{' after '\u'")])),
+            (
+                rust,
+                r"\u{41",
+                Err(&[(10, 5, "unterminated unicode escape")]),
+            ),
+            (rust, r"\u{}", Err(&[(10, 4, "empty unicode escape")])),
+            (
+                rust,
+                r"\u{1000000}",
+                Err(&[(10, 11, "unicode escape has more than 6 hex digits")]),
+            ),
+            (
+                rust,
+                r"\u{D800}",
+                Err(&[(10, 8, "invalid unicode code point 0xD800")]),
+            ),
+            (rust, r"\101", Err(&[(10, 2, r"unknown escape '\1'")])),
+            (rust, r"\0", Ok("\0")),
+            (c, r"\101\0\7x", Ok("A\0\u{7}x")),
+            (c, r"\1012", Ok("A2")),
+            (c, r"\400", Err(&[(10, 4, "octal escape out of range")])),
+            (
+                c,
+                r"\q \u{41}",
+                Err(&[
+                    (10, 2, r"unknown escape '\q'"),
+                    (13, 2, r"unknown escape '\u'"),
+                ]),
+            ),
+        ];
+
+        for (profile, text, expected) in test_cases {
+            let span: Span = Span::new(10, text.len() as u32);
+            let actual: Result<String, Vec<ParseError>> = unescape(text, span, *profile);
+            let expected: Result<String, Vec<ParseError>> = match expected {
+                Ok(value) => Ok(value.to_string()),
+                Err(errors) => Err(errors
+                    .iter()
+                    .map(|(offset, len, message)| {
+                        ParseError::new(Span::new(*offset, *len), *message)
+                    })
+                    .collect()),
+            };
+            assert_eq!(actual, expected, "text: {:?}", text);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::lexer::Span;
+
+/// A text edit. Replaces a byte span of the old source text with new text.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Edit {
+    span: Span,
+    new_len: u32,
+}
+
+impl Edit {
+    //! Construction
+
+    /// Creates a new edit replacing the `span` of the old source with the `text`.
+    pub fn new(span: Span, text: &str) -> Self {
+        debug_assert!(text.len() <= u32::MAX as usize);
+
+        Self {
+            span,
+            new_len: text.len() as u32,
+        }
+    }
+}
+
+impl Edit {
+    //! Properties
+
+    /// Gets the replaced span of the old source.
+    pub const fn span(self) -> Span {
+        self.span
+    }
+
+    /// Gets the byte length of the new text.
+    pub const fn new_len(self) -> u32 {
+        self.new_len
+    }
+
+    /// Gets the end byte offset of the new text in the new source.
+    pub const fn new_end(self) -> u32 {
+        self.span.offset() + self.new_len
+    }
+
+    /// Gets the change in source length. (`new_len - span.len`)
+    pub const fn delta(self) -> i64 {
+        self.new_len as i64 - self.span.len() as i64
+    }
+}
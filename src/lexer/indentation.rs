@@ -0,0 +1,246 @@
+use crate::lexer::{Span, Token, TokenKind};
+use crate::parser::ParseError;
+
+/// An indentation pass. Adds zero-width layout tokens to lexed tokens for indentation-sensitive
+/// languages.
+///
+/// At the first significant token of each line, a `newline` token ends the previous line and
+/// `indent` or `dedent` tokens open or close indentation levels. Lines inside brackets continue
+/// the previous line, and trivia (e.g. whitespace and comments) never starts a line, so blank and
+/// comment-only lines are ignored. The `EndOfFile` token is preceded by a final `newline` and a
+/// `dedent` for each open level.
+///
+/// Mixed tabs and spaces in an indentation, and dedents that do not match an outer indentation
+/// level, are reported as errors spanning the indentation. After a dedent error, the innermost
+/// closed level continues at the offending indentation.
+#[derive(Clone, Debug)]
+pub struct Indentation<K> {
+    indent: K,
+    dedent: K,
+    newline: K,
+    trivia: Vec<K>,
+    brackets: Vec<(K, K)>,
+}
+
+impl<K> Indentation<K> {
+    //! Construction
+
+    /// Creates a new indentation pass emitting the `indent`, `dedent` and `newline` kinds.
+    pub fn new(indent: K, dedent: K, newline: K) -> Self {
+        Self {
+            indent,
+            dedent,
+            newline,
+            trivia: Vec::default(),
+            brackets: Vec::default(),
+        }
+    }
+}
+
+impl<K> Indentation<K> {
+    //! Configuration
+
+    /// Adds a trivia token kind. Trivia tokens are passed through and never start a line.
+    pub fn add_trivia(&mut self, kind: K) {
+        self.trivia.push(kind);
+    }
+
+    /// Adds a trivia token kind. (builder pattern)
+    #[must_use]
+    pub fn with_trivia(mut self, kind: K) -> Self {
+        self.add_trivia(kind);
+        self
+    }
+
+    /// Adds a pair of `open` and `close` bracket kinds. Indentation is ignored inside brackets.
+    pub fn add_brackets(&mut self, open: K, close: K) {
+        self.brackets.push((open, close));
+    }
+
+    /// Adds a pair of `open` and `close` bracket kinds. (builder pattern)
+    #[must_use]
+    pub fn with_brackets(mut self, open: K, close: K) -> Self {
+        self.add_brackets(open, close);
+        self
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind> Indentation<K> {
+    //! Applying
+
+    /// Adds the layout tokens to the `tokens` lexed from the `source`.
+    pub fn apply(&self, tokens: &[Token<K>], source: &str) -> (Vec<Token<K>>, Vec<ParseError>) {
+        let mut result: Vec<Token<K>> = Vec::with_capacity(tokens.len());
+        let mut errors: Vec<ParseError> = Vec::default();
+        let mut levels: Vec<&str> = vec![""];
+        let mut depth: usize = 0;
+
+        // the result index after the previous significant token, and its end offset
+        let mut previous: Option<(usize, u32)> = None;
+
+        for token in tokens {
+            let kind: K = token.kind();
+            let offset: u32 = token.span().offset();
+            if self.trivia.contains(&kind) {
+                result.push(*token);
+                continue;
+            }
+            if kind == K::end_of_file() {
+                if let Some((index, end)) = previous {
+                    result.insert(index, Token::new(self.newline, Span::new(end, 0)));
+                }
+                for _ in 1..levels.len() {
+                    result.push(Token::new(self.dedent, Span::new(offset, 0)));
+                }
+                result.push(*token);
+                continue;
+            }
+
+            // only the gap since the previous significant token can start a new line
+            let gap_start: usize = previous.map_or(0, |(_, end)| end as usize);
+            let newline: Option<usize> = source[gap_start..offset as usize].rfind('\n');
+            let line_start: usize = newline.map_or(gap_start, |i| gap_start + i + 1);
+            let starts_line: bool = previous.is_none() || newline.is_some();
+            if starts_line && depth == 0 {
+                if let Some((index, end)) = previous {
+                    result.insert(index, Token::new(self.newline, Span::new(end, 0)));
+                }
+                let line: &str = &source[line_start..offset as usize];
+                let indentation: &str =
+                    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+                let span: Span = Span::new(line_start as u32, indentation.len() as u32);
+                if indentation.contains(' ') && indentation.contains('\t') {
+                    errors.push(ParseError::new(
+                        span,
+                        "mixed tabs and spaces in indentation",
+                    ));
+                }
+
+                let mut dedented: bool = false;
+                while levels.len() > 1 && !indentation.starts_with(levels[levels.len() - 1]) {
+                    levels.pop();
+                    result.push(Token::new(self.dedent, Span::new(offset, 0)));
+                    dedented = true;
+                }
+                if indentation.len() > levels[levels.len() - 1].len() {
+                    if dedented {
+                        let message: &str = "dedent does not match any outer indentation level";
+                        errors.push(ParseError::new(span, message));
+
+                        // the innermost closed level continues at the offending indentation
+                        result.pop();
+                        levels.push(indentation);
+                    } else {
+                        levels.push(indentation);
+                        result.push(Token::new(self.indent, Span::new(offset, 0)));
+                    }
+                }
+            }
+
+            if self.brackets.iter().any(|(open, _)| *open == kind) {
+                depth += 1;
+            } else if self.brackets.iter().any(|(_, close)| *close == kind) {
+                depth = depth.saturating_sub(1);
+            }
+            result.push(*token);
+            previous = Some((result.len(), token.span().end()));
+        }
+
+        (result, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::matchers::{ident, whitespace};
+    use crate::lexer::{Indentation, Lexer, Span, Token};
+    use crate::parser::ParseError;
+    use crate::{line_comment, literal};
+
+    crate::lexer! {
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        enum Kind {
+            Whitespace: whitespace,
+            Comment: line_comment!("#"),
+            Ident: ident,
+            Colon: literal!(":"),
+            LParen: literal!("("),
+            RParen: literal!(")"),
+            Indent,
+            Dedent,
+            Newline,
+        }
+    }
+
+    /// Applies the indentation pass and returns the significant kinds and the errors.
+    fn layout(source: &str) -> (Vec<Kind>, Vec<ParseError>) {
+        let lexer: Lexer<Kind> = Kind::lexer();
+        let indentation: Indentation<Kind> =
+            Indentation::new(Kind::Indent, Kind::Dedent, Kind::Newline)
+                .with_trivia(Kind::Whitespace)
+                .with_trivia(Kind::Comment)
+                .with_brackets(Kind::LParen, Kind::RParen);
+        let (tokens, errors): (Vec<Token<Kind>>, Vec<ParseError>) =
+            indentation.apply(&lexer.lex(source), source);
+
+        assert!(tokens.is_sorted_by_key(|t| t.span().offset()));
+        let kinds: Vec<Kind> = tokens
+            .iter()
+            .map(|t| t.kind())
+            .filter(|k| *k != Kind::Whitespace && *k != Kind::Comment)
+            .collect();
+        (kinds, errors)
+    }
+
+    #[test]
+    fn apply() {
+        use Kind::*;
+
+        let source: &str = "a:\n  b\n\n  # comment\n  c:\n    d\ne\n";
+        let (kinds, errors): (Vec<Kind>, Vec<ParseError>) = layout(source);
+        assert!(errors.is_empty(), "errors: {:?}", errors);
+        assert_eq!(
+            kinds,
+            [
+                Ident, Colon, Newline, Indent, Ident, Newline, Ident, Colon, Newline, Indent,
+                Ident, Newline, Dedent, Dedent, Ident, Newline, EndOfFile,
+            ]
+        );
+
+        let (kinds, _): (Vec<Kind>, Vec<ParseError>) = layout("a(\n  b\n)\n  c");
+        assert_eq!(
+            kinds,
+            [
+                Ident, LParen, Ident, RParen, Newline, Indent, Ident, Newline, Dedent, EndOfFile,
+            ]
+        );
+
+        let (kinds, _): (Vec<Kind>, Vec<ParseError>) = layout("");
+        assert_eq!(kinds, [EndOfFile]);
+    }
+
+    #[test]
+    fn apply_errors() {
+        use Kind::*;
+
+        let (kinds, errors): (Vec<Kind>, Vec<ParseError>) = layout("a\n    b\n  c\n  d\n");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span(), Span::new(8, 2));
+        assert_eq!(
+            errors[0].message(),
+            "dedent does not match any outer indentation level"
+        );
+        assert_eq!(
+            kinds,
+            [
+                Ident, Newline, Indent, Ident, Newline, Ident, Newline, Ident, Newline, Dedent,
+                EndOfFile,
+            ]
+        );
+
+        let (_, errors): (Vec<Kind>, Vec<ParseError>) = layout("a\n \tb\n");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span(), Span::new(2, 2));
+        assert_eq!(errors[0].message(), "mixed tabs and spaces in indentation");
+    }
+}
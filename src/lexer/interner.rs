@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// An interned string. Resolved with the [Interner] that created it.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    //! Properties
+
+    /// Gets the symbol index. (0-indexed, in interning order)
+    pub const fn index(self) -> u32 {
+        self.0
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "symbol[{}]", self.0)
+    }
+}
+
+/// A string interner. Maps equal strings to the same [Symbol].
+#[derive(Clone, Default, Debug)]
+pub struct Interner {
+    strings: Vec<String>,
+    symbols: HashMap<String, Symbol>,
+}
+
+impl Interner {
+    //! Interning
+
+    /// Interns the `text`.
+    pub fn intern(&mut self, text: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(text) {
+            return *symbol;
+        }
+        debug_assert!(self.strings.len() < u32::MAX as usize);
+
+        let symbol: Symbol = Symbol(self.strings.len() as u32);
+        self.strings.push(text.to_string());
+        self.symbols.insert(text.to_string(), symbol);
+        symbol
+    }
+
+    /// Gets the text of the `symbol`.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+
+    /// Gets the number of interned strings.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Checks if no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern() {
+        let mut interner: Interner = Interner::default();
+        let a: Symbol = interner.intern("a");
+        let b: Symbol = interner.intern("b");
+        assert_ne!(a, b);
+        assert_eq!(interner.intern("a"), a);
+        assert_eq!(interner.resolve(a), "a");
+        assert_eq!(interner.resolve(b), "b");
+        assert_eq!(interner.len(), 2);
+    }
+}
@@ -0,0 +1,203 @@
+/// A keyword table. Classifies matched text as a keyword kind using a perfect hash.
+///
+/// Used to turn identifiers into keywords after a single identifier match instead of trying a
+/// rule for each keyword. The hash is built by hash-and-displace: the keywords are grouped into
+/// small buckets and each bucket gets a displacement that places its keywords in free slots, so
+/// the table stays within a small factor of the number of keywords.
+#[derive(Clone, Debug)]
+pub struct Keywords<K> {
+    seed: u64,
+    mask: usize,
+    displacements: Vec<u64>,
+    slots: Vec<Option<(String, K)>>,
+}
+
+impl<K> Keywords<K> {
+    //! Construction
+
+    /// The average number of keywords per bucket.
+    const BUCKET_SIZE: usize = 4;
+
+    /// The maximum number of displacements tried per bucket.
+    const MAX_DISPLACEMENTS: u64 = 1 << 16;
+
+    /// The maximum number of seeds tried per table size.
+    const MAX_SEEDS: u64 = 16;
+
+    /// Creates a new keyword table from `(text, kind)` entries.
+    ///
+    /// If a text appears more than once the first entry wins.
+    pub fn new<S: Into<String>>(entries: impl IntoIterator<Item = (S, K)>) -> Self {
+        let mut unique: Vec<(String, K)> = Vec::default();
+        for (text, kind) in entries {
+            let text: String = text.into();
+            if !unique.iter().any(|(t, _)| *t == text) {
+                unique.push((text, kind));
+            }
+        }
+
+        let buckets: usize = unique.len().div_ceil(Self::BUCKET_SIZE).max(1);
+        let mut size: usize = (unique.len() + unique.len() / 4).max(1).next_power_of_two();
+        loop {
+            for seed in 0..Self::MAX_SEEDS {
+                if let Some((displacements, slots)) = Self::place(&unique, seed, buckets, size - 1)
+                {
+                    let mut table: Vec<Option<(String, K)>> = Vec::with_capacity(size);
+                    table.resize_with(size, || None);
+                    for ((text, kind), slot) in unique.into_iter().zip(slots) {
+                        table[slot] = Some((text, kind));
+                    }
+                    return Self {
+                        seed,
+                        mask: size - 1,
+                        displacements,
+                        slots: table,
+                    };
+                }
+            }
+            size *= 2;
+        }
+    }
+
+    /// Finds a displacement for each of the `buckets` that places the entries without collisions,
+    /// largest buckets first. Returns the displacements and the slot of each entry.
+    fn place(
+        entries: &[(String, K)],
+        seed: u64,
+        buckets: usize,
+        mask: usize,
+    ) -> Option<(Vec<u64>, Vec<usize>)> {
+        let hashes: Vec<u64> = entries
+            .iter()
+            .map(|(text, _)| Self::hash(text, seed))
+            .collect();
+        let mut members: Vec<Vec<usize>> = vec![Vec::default(); buckets];
+        for (entry, hash) in hashes.iter().enumerate() {
+            members[Self::bucket(*hash, buckets)].push(entry);
+        }
+        let mut order: Vec<usize> = (0..buckets).collect();
+        order.sort_by_key(|bucket| std::cmp::Reverse(members[*bucket].len()));
+
+        let mut used: Vec<bool> = vec![false; mask + 1];
+        let mut displacements: Vec<u64> = vec![0; buckets];
+        let mut slots: Vec<usize> = vec![0; entries.len()];
+        let mut candidate: Vec<usize> = Vec::default();
+        for bucket in order {
+            if members[bucket].is_empty() {
+                break;
+            }
+            let displacement: u64 = (0..Self::MAX_DISPLACEMENTS).find(|displacement| {
+                candidate.clear();
+                for entry in &members[bucket] {
+                    let slot: usize = Self::displace(hashes[*entry], *displacement) as usize & mask;
+                    if used[slot] || candidate.contains(&slot) {
+                        return false;
+                    }
+                    candidate.push(slot);
+                }
+                true
+            })?;
+            displacements[bucket] = displacement;
+            for (entry, slot) in members[bucket].iter().zip(&candidate) {
+                used[*slot] = true;
+                slots[*entry] = *slot;
+            }
+        }
+        Some((displacements, slots))
+    }
+
+    /// Hashes the `text` with the `seed`. (FNV-1a with a final mix)
+    fn hash(text: &str, seed: u64) -> u64 {
+        let mut hash: u64 = 0xCBF2_9CE4_8422_2325 ^ seed.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        for byte in text.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01B3);
+        }
+        hash ^ (hash >> 29)
+    }
+
+    /// Gets the bucket of a `hash` among the `buckets`.
+    fn bucket(hash: u64, buckets: usize) -> usize {
+        (hash >> 32) as usize % buckets
+    }
+
+    /// Mixes the `hash` with a bucket `displacement` into a slot hash.
+    fn displace(hash: u64, displacement: u64) -> u64 {
+        let mut x: u64 = hash ^ displacement.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        x = (x ^ (x >> 32)).wrapping_mul(0xD6E8_FEB8_6659_FD93);
+        x ^ (x >> 32)
+    }
+}
+
+impl<K: Copy> Keywords<K> {
+    //! Lookup
+
+    /// Gets the keyword kind of the `text`.
+    pub fn get(&self, text: &str) -> Option<K> {
+        let hash: u64 = Self::hash(text, self.seed);
+        let displacement: u64 = self.displacements[Self::bucket(hash, self.displacements.len())];
+        match &self.slots[Self::displace(hash, displacement) as usize & self.mask] {
+            Some((keyword, kind)) if keyword == text => Some(*kind),
+            _ => None,
+        }
+    }
+}
+
+impl<K> Keywords<K> {
+    //! Properties
+
+    /// Gets the number of keywords.
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Checks if there are no keywords.
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get() {
+        let words: Vec<String> = (0..200).map(|i| format!("kw{i}")).collect();
+        let keywords: Keywords<usize> = Keywords::new(words.iter().cloned().zip(0..));
+
+        assert_eq!(keywords.len(), 200);
+        for (i, word) in words.iter().enumerate() {
+            assert_eq!(keywords.get(word), Some(i));
+        }
+        assert_eq!(keywords.get("kw200"), None);
+        assert_eq!(keywords.get(""), None);
+    }
+
+    #[test]
+    fn size() {
+        for count in [1usize, 10, 100, 1000, 5000] {
+            let words: Vec<String> = (0..count).map(|i| format!("word{i}")).collect();
+            let keywords: Keywords<usize> = Keywords::new(words.iter().cloned().zip(0..));
+
+            assert!(
+                keywords.slots.len() <= (count + count / 4).next_power_of_two(),
+                "count: {count}"
+            );
+            for (i, word) in words.iter().enumerate() {
+                assert_eq!(keywords.get(word), Some(i));
+            }
+        }
+    }
+
+    #[test]
+    fn duplicates() {
+        let keywords: Keywords<u8> = Keywords::new([("if", 1), ("else", 2), ("if", 3)]);
+        assert_eq!(keywords.len(), 2);
+        assert_eq!(keywords.get("if"), Some(1));
+
+        let keywords: Keywords<u8> = Keywords::new(Vec::<(&str, u8)>::new());
+        assert!(keywords.is_empty());
+        assert_eq!(keywords.get("if"), None);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::lexer::Matcher;
+use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// The state carried between tokens while lexing.
+#[derive(Clone, Debug)]
+pub(in crate::lexer) struct LexState<K> {
+    /// The mode stack. The last mode is active.
+    pub(in crate::lexer) stack: Vec<usize>,
+
+    /// The kind of the previous significant (non-trivia) token.
+    pub(in crate::lexer) previous: Option<K>,
+
+    /// The bodies deferred to the following lines by tokens on the current line, in order.
+    pub(in crate::lexer) deferred: VecDeque<Deferred<K>>,
+
+    /// The offset of the next deferred body from the current position, once its line break is
+    /// found.
+    pub(in crate::lexer) body_start: Option<usize>,
+}
+
+/// A body deferred to the following lines, like the body of a heredoc.
+#[derive(Clone)]
+pub(in crate::lexer) struct Deferred<K> {
+    /// The token kind of the body.
+    pub(in crate::lexer) kind: K,
+
+    /// The body matcher.
+    pub(in crate::lexer) matcher: Arc<dyn Matcher>,
+}
+
+impl<K> Default for LexState<K> {
+    fn default() -> Self {
+        Self {
+            stack: vec![0],
+            previous: None,
+            deferred: VecDeque::default(),
+            body_start: None,
+        }
+    }
+}
+
+impl<K> LexState<K> {
+    //! Properties
+
+    /// Gets the active mode index.
+    pub(in crate::lexer) fn mode(&self) -> usize {
+        *self.stack.last().unwrap()
+    }
+}
+
+impl<K: Debug> Debug for Deferred<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Deferred")
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
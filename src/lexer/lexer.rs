@@ -1,79 +1,526 @@
-use crate::lexer::{Rule, Span, Token, TokenKind};
+use crate::lexer::lex_state::{Deferred, LexState};
+use crate::lexer::matched::Matched;
+use crate::lexer::mode::{Mode, Segment};
+use crate::lexer::{
+    Edit, MatchStrategy, Matcher, ModeStacks, Rule, Span, StreamTokens, Token, TokenKind, Tokens,
+    Transition, TriviaTokens, UnrecognizedPolicy, Values,
+};
+use crate::parser::ParseError;
+use std::io::BufRead;
+
+/// The name of the default lexer mode. Lexing starts in this mode.
+pub const DEFAULT_MODE: &str = "default";
 
 /// A lexer. Converts source text into a sequence of tokens using ordered rules.
+///
+/// Rules are grouped into named modes. Lexing starts in the [DEFAULT_MODE] and rules with a
+/// [Transition] push, pop, or switch the active mode when they match.
+///
+/// The [MatchStrategy] decides which rule wins when several rules match, and the
+/// [UnrecognizedPolicy] decides how input no rule matches is turned into tokens.
 #[derive(Clone, Debug)]
 pub struct Lexer<K> {
-    rules: Vec<Rule<K>>,
+    modes: Vec<Mode<K>>,
+    strategy: MatchStrategy,
+    trivia: Vec<K>,
+    unrecognized: UnrecognizedPolicy,
 }
 
 impl<K> Default for Lexer<K> {
     fn default() -> Self {
         Self {
-            rules: Vec::default(),
+            modes: vec![Mode::new(DEFAULT_MODE)],
+            strategy: MatchStrategy::default(),
+            trivia: Vec::default(),
+            unrecognized: UnrecognizedPolicy::default(),
         }
     }
 }
 
+impl<K> Lexer<K> {
+    //! Strategy
+
+    /// Gets the rule selection strategy.
+    pub fn strategy(&self) -> MatchStrategy {
+        self.strategy
+    }
+
+    /// Sets the rule selection `strategy`.
+    pub fn set_strategy(&mut self, strategy: MatchStrategy) {
+        self.strategy = strategy;
+    }
+
+    /// Sets the rule selection `strategy`. (builder pattern)
+    #[must_use]
+    pub fn with_strategy(mut self, strategy: MatchStrategy) -> Self {
+        self.set_strategy(strategy);
+        self
+    }
+}
+
+impl<K> Lexer<K> {
+    //! Unrecognized Input
+
+    /// Gets the unrecognized input policy.
+    pub fn unrecognized(&self) -> UnrecognizedPolicy {
+        self.unrecognized
+    }
+
+    /// Sets the unrecognized input `policy`.
+    pub fn set_unrecognized(&mut self, policy: UnrecognizedPolicy) {
+        self.unrecognized = policy;
+    }
+
+    /// Sets the unrecognized input `policy`. (builder pattern)
+    #[must_use]
+    pub fn with_unrecognized(mut self, policy: UnrecognizedPolicy) -> Self {
+        self.set_unrecognized(policy);
+        self
+    }
+}
+
+impl<K> Lexer<K> {
+    //! Trivia
+
+    /// Adds a trivia token kind. (e.g. whitespace and comments)
+    pub fn add_trivia(&mut self, kind: K) {
+        self.trivia.push(kind);
+    }
+
+    /// Adds a trivia token kind. (builder pattern)
+    #[must_use]
+    pub fn with_trivia(mut self, kind: K) -> Self {
+        self.add_trivia(kind);
+        self
+    }
+
+    /// Gets the trivia token kinds.
+    pub fn trivia(&self) -> &[K] {
+        &self.trivia
+    }
+}
+
 impl<K> Lexer<K> {
     //! Rules
 
-    /// Adds a rule to the lexer.
-    pub fn add_rule(&mut self, kind: K, matcher: fn(&str) -> Option<usize>) {
-        self.rules.push(Rule::new(kind, matcher));
+    /// Adds a rule to the default mode.
+    pub fn add_rule(&mut self, kind: K, matcher: impl Matcher + 'static) {
+        self.add_mode_rule(DEFAULT_MODE, Rule::new(kind, matcher));
     }
 
-    /// Adds a rule to the lexer. (builder pattern)
+    /// Adds a rule to the default mode. (builder pattern)
     #[must_use]
-    pub fn with_rule(mut self, kind: K, matcher: fn(&str) -> Option<usize>) -> Self {
+    pub fn with_rule(mut self, kind: K, matcher: impl Matcher + 'static) -> Self {
         self.add_rule(kind, matcher);
         self
     }
 }
 
-impl<K: Copy + TokenKind> Lexer<K> {
+impl<K> Lexer<K> {
+    //! Modes
+
+    /// Adds a `rule` to the `mode`. The mode is created if it does not exist.
+    ///
+    /// The rule's transition target mode is also created if it does not exist.
+    pub fn add_mode_rule(&mut self, mode: &str, rule: Rule<K>) {
+        if let Some(target) = rule.transition().and_then(Transition::target) {
+            self.mode_index(target);
+        }
+        let index: usize = self.mode_index(mode);
+        self.modes[index].push_rule(rule);
+    }
+
+    /// Adds a `rule` to the `mode`. (builder pattern)
+    #[must_use]
+    pub fn with_mode_rule(mut self, mode: &str, rule: Rule<K>) -> Self {
+        self.add_mode_rule(mode, rule);
+        self
+    }
+
+    /// Gets the mode names in the order they were created.
+    pub fn modes(&self) -> impl Iterator<Item = &str> {
+        self.modes.iter().map(|mode| mode.name.as_str())
+    }
+
+    /// Gets the index of the `mode`. Creates the mode if it does not exist.
+    fn mode_index(&mut self, mode: &str) -> usize {
+        match self.find_mode(mode) {
+            Some(index) => index,
+            None => {
+                self.modes.push(Mode::new(mode));
+                self.modes.len() - 1
+            }
+        }
+    }
+
+    /// Finds the index of the `mode`.
+    fn find_mode(&self, mode: &str) -> Option<usize> {
+        self.modes.iter().position(|m| m.name == mode)
+    }
+
+    /// Applies the `transition` to the mode stack of the `state`.
+    fn apply_transition(&self, transition: &Transition, state: &mut LexState<K>) {
+        match transition {
+            Transition::Push(mode) => state.stack.push(self.find_mode(mode).unwrap()),
+            Transition::Pop => {
+                if state.stack.len() > 1 {
+                    state.stack.pop();
+                }
+            }
+            Transition::Switch(mode) => {
+                *state.stack.last_mut().unwrap() = self.find_mode(mode).unwrap()
+            }
+        }
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind> Lexer<K> {
     //! Lexing
 
     /// Lexes the `source` into a sequence of tokens.
     pub fn lex(&self, source: &str) -> Vec<Token<K>> {
-        debug_assert!(source.len() <= u32::MAX as usize);
+        self.tokens(source).collect()
+    }
+
+    /// Lexes the `source` lazily. The iterator ends with the `EndOfFile` token.
+    pub fn tokens<'a>(&'a self, source: &'a str) -> Tokens<'a, K> {
+        Tokens::new(self, source, 0)
+    }
+
+    /// Lexes the `reader` as a stream with bounded memory. Tokens use absolute 64-bit offsets.
+    pub fn stream<R: BufRead>(&self, reader: R) -> StreamTokens<'_, K, R> {
+        StreamTokens::new(self, reader)
+    }
+
+    /// Lexes one token from the non-empty `remaining` source and advances the `state`.
+    ///
+    /// Returns the token kind and length, and what matched it. Bodies deferred by a token are
+    /// matched at the start of the line after it.
+    pub(in crate::lexer) fn step(
+        &self,
+        remaining: &str,
+        state: &mut LexState<K>,
+    ) -> (K, usize, Option<Matched<'_, K>>) {
+        let (kind, len, matched): (K, usize, Option<Matched<K>>) =
+            match self.step_body(remaining, state) {
+                Some(body) => body,
+                None => self.step_rule(remaining, state),
+            };
+        if let Some(body_start) = &mut state.body_start {
+            *body_start -= len;
+        }
+        if !self.trivia.contains(&kind) {
+            state.previous = Some(kind);
+        }
+        (kind, len, matched)
+    }
+
+    /// Matches the next deferred body of the `state` if the `remaining` source starts at it.
+    fn step_body(
+        &self,
+        remaining: &str,
+        state: &mut LexState<K>,
+    ) -> Option<(K, usize, Option<Matched<'_, K>>)> {
+        if state.body_start != Some(0) {
+            return None;
+        }
+        let deferred: Deferred<K> = state.deferred.pop_front().unwrap();
+        state.body_start = None;
+        let len: usize = deferred
+            .matcher
+            .try_match(remaining)
+            .filter(|len| *len > 0)?;
+        Some((deferred.kind, len, Some(Matched::Body(deferred.matcher))))
+    }
+
+    /// Matches the rules against the `remaining` source. Tokens end at the line break before a
+    /// deferred body.
+    fn step_rule(
+        &self,
+        remaining: &str,
+        state: &mut LexState<K>,
+    ) -> (K, usize, Option<Matched<'_, K>>) {
+        if !state.deferred.is_empty() && state.body_start.is_none() {
+            state.body_start = remaining.find('\n').map(|i| i + 1);
+        }
+        let line: &str = &remaining[..state.body_start.unwrap_or(remaining.len())];
+        match self.match_rule(state, line) {
+            Some((rule, len)) => {
+                if let Some(transition) = rule.transition() {
+                    self.apply_transition(transition, state);
+                }
+                if let Some(matcher) = rule.deferred_body(&line[..len]) {
+                    let kind: K = rule.kind();
+                    state.deferred.push_back(Deferred { kind, matcher });
+                }
+                (rule.classify(&line[..len]), len, Some(Matched::Rule(rule)))
+            }
+            None => (K::unrecognized(), self.unrecognized_len(state, line), None),
+        }
+    }
+
+    /// Gets the length of the unrecognized input at the start of the `remaining` source.
+    fn unrecognized_len(&self, state: &LexState<K>, remaining: &str) -> usize {
+        let mut chars = remaining.char_indices().skip(1);
+        match self.unrecognized {
+            UnrecognizedPolicy::PerChar => chars.next(),
+            UnrecognizedPolicy::Coalesce => {
+                chars.find(|(i, _)| self.match_rule(state, &remaining[*i..]).is_some())
+            }
+        }
+        .map_or(remaining.len(), |(i, _)| i)
+    }
+
+    /// Matches the rules of the active mode of the `state` against the `remaining` source using
+    /// the strategy. Rules whose guard rejects the previous token kind are skipped.
+    ///
+    /// Returns the selected rule and the match length.
+    fn match_rule(&self, state: &LexState<K>, remaining: &str) -> Option<(&Rule<K>, usize)> {
+        let mode: &Mode<K> = &self.modes[state.mode()];
+        let mut best: Option<(usize, usize)> = None;
+        let first: u8 = remaining.as_bytes()[0];
+        for segment in &mode.dispatch[first as usize] {
+            let found: Option<(usize, usize)> = match &mode.segments[*segment] {
+                Segment::Rule(index) => Some(&mode.rules[*index])
+                    .filter(|rule| rule.allows(state.previous))
+                    .and_then(|rule| rule.try_match(remaining))
+                    .filter(|len| *len > 0)
+                    .map(|len| (*index, len)),
+                Segment::Literals(trie) => trie.find(remaining, self.strategy),
+            };
+            if let Some((index, len)) = found
+                && best.is_none_or(|(_, best_len)| len > best_len)
+            {
+                best = Some((index, len));
+                if self.strategy == MatchStrategy::FirstMatch {
+                    break;
+                }
+            }
+        }
+        best.map(|(index, len)| (&mode.rules[index], len))
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind> Lexer<K> {
+    //! Values
+
+    /// Lexes the `source` and decodes the values of tokens matched by rules with a decoder.
+    pub fn lex_values(&self, source: &str) -> (Vec<Token<K>>, Values) {
+        let mut tokens: Vec<Token<K>> = Vec::default();
+        let mut values: Values = Values::default();
+        let mut iter: Tokens<K> = self.tokens(source);
+        while let Some((token, matched)) = iter.next_match() {
+            if let Some(matched) = matched
+                && let Some(value) = matched.decode(token.text(source), values.interner_mut())
+            {
+                values.push(token.span(), value);
+            }
+            tokens.push(token);
+        }
+        (tokens, values)
+    }
+}
 
+impl<K: Copy + PartialEq + TokenKind> Lexer<K> {
+    //! Diagnostics
+
+    /// Lexes the `source` and reports an error for each `Unrecognized` token and each token its
+    /// matcher diagnoses as malformed (e.g. an unterminated string).
+    ///
+    /// The errors can be merged with the errors of a [Parser].
+    ///
+    /// [Parser]: crate::parser::Parser
+    pub fn lex_diagnostics(&self, source: &str) -> (Vec<Token<K>>, Vec<ParseError>) {
         let mut tokens: Vec<Token<K>> = Vec::default();
-        let mut pos: usize = 0;
+        let mut errors: Vec<ParseError> = Vec::default();
+        let mut iter: Tokens<K> = self.tokens(source);
+        while let Some((token, matched)) = iter.next_match() {
+            let error: Option<ParseError> = match matched {
+                Some(matched) => matched
+                    .diagnose(token.text(source))
+                    .map(|message| ParseError::new(token.span(), message)),
+                None if !token.span().is_empty() => {
+                    Some(Self::unrecognized_error(token.span(), source))
+                }
+                None => None,
+            };
+            errors.extend(error);
+            tokens.push(token);
+        }
+        (tokens, errors)
+    }
 
-        while pos < source.len() {
-            let remaining: &str = &source[pos..];
-            let (kind, len): (K, usize) = self.match_rule(remaining);
-            debug_assert!(len > 0);
-            debug_assert!(source.is_char_boundary(pos + len));
-            let span: Span = Span::new(pos as u32, len as u32);
-            tokens.push(Token::new(kind, span));
-            pos += len;
+    /// Creates the error for the unrecognized input at the `span` of the `source`.
+    fn unrecognized_error(span: Span, source: &str) -> ParseError {
+        let text: &str = span.text(source);
+        let first: char = text.chars().next().unwrap();
+        let count: usize = text.chars().count();
+        if count == 1 {
+            ParseError::new(span, format!("unexpected character {first:?}"))
+        } else {
+            let message: String = format!("{count} unexpected characters starting with {first:?}");
+            ParseError::new(span, message)
         }
+    }
+}
 
-        let eof_span: Span = Span::new(pos as u32, 0);
-        tokens.push(Token::new(K::end_of_file(), eof_span));
-        tokens
+impl<K: Copy + PartialEq + TokenKind> Lexer<K> {
+    //! Trivia
+
+    /// Lexes the `source` and attaches trivia tokens to the neighbouring significant tokens.
+    pub fn lex_trivia(&self, source: &str) -> TriviaTokens<K> {
+        TriviaTokens::new(self.tokens(source), source, |kind| {
+            self.trivia.contains(&kind)
+        })
     }
+}
+
+impl<K: Copy + PartialEq + TokenKind> Lexer<K> {
+    //! Relexing
 
-    /// Matches the first rule against the `remaining` source.
-    fn match_rule(&self, remaining: &str) -> (K, usize) {
-        for rule in &self.rules {
-            if let Some(len) = rule.try_match(remaining)
-                && len > 0
+    /// Relexes the `source` after an `edit` given the `tokens` lexed from the old source.
+    ///
+    /// Lexing restarts one token before the edit and stops once a token boundary lines up with
+    /// a boundary of the old tokens after the edit. The remaining old tokens are shifted by the
+    /// change in length. This assumes matchers look no further ahead than the end of the next
+    /// token. With guarded rules, a boundary only lines up if the previous significant token
+    /// kinds match as well.
+    ///
+    /// Lexers with multiple modes relex the whole source, since the mode stack at the restart
+    /// position is unknown. Use [Lexer::relex_modes] to relex them incrementally. Lexers with
+    /// rules that defer bodies, like heredocs, always relex the whole source.
+    pub fn relex(&self, tokens: &[Token<K>], edit: Edit, source: &str) -> Vec<Token<K>> {
+        if self.modes.len() > 1 || self.defers_bodies() {
+            return self.lex(source);
+        }
+        self.relex_from(tokens, None, edit, source).0
+    }
+
+    /// Lexes the `source` and records the mode stack at the start of each token.
+    pub fn lex_modes(&self, source: &str) -> (Vec<Token<K>>, ModeStacks) {
+        let mut tokens: Vec<Token<K>> = Vec::default();
+        let mut stacks: ModeStacks = ModeStacks::default();
+        let mut iter: Tokens<K> = self.tokens(source);
+        loop {
+            stacks.record(tokens.len(), iter.stack());
+            match iter.next() {
+                Some(token) => tokens.push(token),
+                None => break,
+            }
+        }
+        (tokens, stacks)
+    }
+
+    /// Relexes the `source` after an `edit` given the `tokens` and mode `stacks` lexed from the
+    /// old source by [Lexer::lex_modes]. Returns the new tokens and mode stacks.
+    ///
+    /// Like [Lexer::relex], but restarts in the recorded mode stack, and a boundary only lines up
+    /// if the mode stacks match as well.
+    pub fn relex_modes(
+        &self,
+        tokens: &[Token<K>],
+        stacks: &ModeStacks,
+        edit: Edit,
+        source: &str,
+    ) -> (Vec<Token<K>>, ModeStacks) {
+        if self.defers_bodies() {
+            return self.lex_modes(source);
+        }
+        self.relex_from(tokens, Some(stacks), edit, source)
+    }
+
+    /// Relexes the `source` after an `edit`, restarting in the mode stacks of the old `stacks`.
+    /// (the default mode if `None`)
+    fn relex_from(
+        &self,
+        tokens: &[Token<K>],
+        stacks: Option<&ModeStacks>,
+        edit: Edit,
+        source: &str,
+    ) -> (Vec<Token<K>>, ModeStacks) {
+        debug_assert!(!tokens.is_empty());
+        debug_assert!(
+            tokens.last().unwrap().span().end() as i64 + edit.delta() == source.len() as i64
+        );
+
+        let edit_start: u32 = edit.span().offset();
+        let touched: usize = tokens.partition_point(|t| t.span().end() < edit_start);
+        let restart: usize = touched.saturating_sub(1).min(tokens.len() - 1);
+
+        let mut result: Vec<Token<K>> = tokens[..restart].to_vec();
+        let mut result_stacks: ModeStacks = stacks.map(|s| s.prefix(restart)).unwrap_or_default();
+        let restart_pos: usize = tokens[restart].span().offset() as usize;
+        let restart_stack: Vec<usize> = stacks.map_or(vec![0], |s| s.get(restart).to_vec());
+        let previous: Option<K> = self.previous_kind(&result);
+        let guarded: bool = self
+            .modes
+            .iter()
+            .any(|mode| mode.rules.iter().any(Rule::is_guarded));
+        let mut iter: Tokens<K> = Tokens::new(self, source, restart_pos)
+            .with_previous(previous)
+            .with_stack(restart_stack);
+        loop {
+            result_stacks.record(result.len(), iter.stack());
+            let Some(token) = iter.next() else {
+                break;
+            };
+            result.push(token);
+            let end: u32 = token.span().end();
+            if end < edit.new_end() || token.span().is_empty() {
+                continue;
+            }
+            let old_end: i64 = end as i64 - edit.delta();
+            let old: &[Token<K>] = &tokens[restart..];
+            let sync: usize = old.partition_point(|t| (t.span().offset() as i64) < old_end);
+            if sync < old.len()
+                && old[sync].span().offset() as i64 == old_end
+                && stacks.is_none_or(|s| s.get(restart + sync) == iter.stack())
+                && (!guarded
+                    || self.previous_kind(&result) == self.previous_kind(&tokens[..restart + sync]))
             {
-                return (rule.kind(), len);
+                if let Some(stacks) = stacks {
+                    result_stacks.record_from(stacks, restart + sync, result.len());
+                }
+                let shifted = old[sync..]
+                    .iter()
+                    .map(|t| Token::new(t.kind(), t.span().shift(edit.delta())));
+                result.extend(shifted);
+                break;
             }
         }
-        let c: char = remaining.chars().next().unwrap();
-        (K::unrecognized(), c.len_utf8())
+        (result, result_stacks)
+    }
+
+    /// Checks if any rule may defer a body, whose pending state at the restart position is unknown.
+    fn defers_bodies(&self) -> bool {
+        self.modes
+            .iter()
+            .any(|mode| mode.rules.iter().any(Rule::defers_bodies))
+    }
+
+    /// Gets the kind of the last significant (non-trivia) token in the `tokens`.
+    fn previous_kind(&self, tokens: &[Token<K>]) -> Option<K> {
+        tokens
+            .iter()
+            .rev()
+            .map(|token| token.kind())
+            .find(|kind| !self.trivia.contains(kind))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::lexer::matchers::{digits, ident, whitespace};
-    use crate::lexer::{Lexer, Span, Token};
-    use crate::literal;
+    use crate::lexer::decoders::{int, symbol};
+    use crate::lexer::matchers::{Heredoc, Literal, StringLiteral, digits, ident, whitespace};
+    use crate::lexer::mode::{Mode, Segment};
+    use crate::lexer::{
+        ByteSet, DEFAULT_MODE, Edit, Lexer, MatchStrategy, Matcher, ModeStacks, Rule, Span, Symbol,
+        Token, TriviaTokens, UnrecognizedPolicy, Value, Values,
+    };
+    use crate::parser::ParseError;
+    use crate::{keyword, line_comment, literal};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     crate::lexer! {
         #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -85,6 +532,7 @@ mod tests {
             RBrace: literal!("}"),
             Semi: literal!(";"),
             Eq: literal!("="),
+            Str,
         }
     }
 
@@ -143,6 +591,557 @@ mod tests {
         assert_eq!(tokens[0].kind(), Kind::EndOfFile);
     }
 
+    #[test]
+    fn lex_closure_rule() {
+        let delimiter: String = "#".to_string();
+        let lexer: Lexer<Kind> = Lexer::default()
+            .with_rule(Kind::Whitespace, whitespace)
+            .with_rule(Kind::Semi, move |source: &str| {
+                source.starts_with(&delimiter).then_some(delimiter.len())
+            })
+            .with_rule(Kind::Ident, ident);
+        let source: &str = "a # b";
+        let tokens: Vec<Token<Kind>> = lexer.lex(source);
+
+        let expected: &[(Kind, &str)] = &[
+            (Kind::Ident, "a"),
+            (Kind::Whitespace, " "),
+            (Kind::Semi, "#"),
+            (Kind::Whitespace, " "),
+            (Kind::Ident, "b"),
+            (Kind::EndOfFile, ""),
+        ];
+
+        assert_eq!(tokens.len(), expected.len());
+        for (token, (kind, text)) in tokens.iter().zip(expected) {
+            assert_eq!(token.kind(), *kind);
+            assert_eq!(token.text(source), *text);
+        }
+    }
+
+    #[test]
+    fn lex_modes() {
+        let lexer: Lexer<Kind> = Lexer::default()
+            .with_mode_rule(
+                DEFAULT_MODE,
+                Rule::new(Kind::LBrace, literal!("{")).with_push("block"),
+            )
+            .with_rule(Kind::Ident, ident)
+            .with_mode_rule("block", Rule::new(Kind::RBrace, literal!("}")).with_pop())
+            .with_mode_rule("block", Rule::new(Kind::Int, digits))
+            .with_mode_rule(
+                "block",
+                Rule::new(Kind::Semi, literal!(";")).with_switch("stmt"),
+            )
+            .with_mode_rule("stmt", Rule::new(Kind::Whitespace, whitespace))
+            .with_mode_rule("stmt", Rule::new(Kind::RBrace, literal!("}")).with_pop());
+        let source: &str = "a{1;  }}b";
+        let tokens: Vec<Token<Kind>> = lexer.lex(source);
+
+        let expected: &[(Kind, &str)] = &[
+            (Kind::Ident, "a"),
+            (Kind::LBrace, "{"),
+            (Kind::Int, "1"),
+            (Kind::Semi, ";"),
+            (Kind::Whitespace, "  "),
+            (Kind::RBrace, "}"),
+            (Kind::Unrecognized, "}"),
+            (Kind::Ident, "b"),
+            (Kind::EndOfFile, ""),
+        ];
+
+        assert_eq!(tokens.len(), expected.len());
+        for (token, (kind, text)) in tokens.iter().zip(expected) {
+            assert_eq!(token.kind(), *kind);
+            assert_eq!(token.text(source), *text);
+        }
+        assert_eq!(
+            lexer.modes().collect::<Vec<&str>>(),
+            [DEFAULT_MODE, "block", "stmt"]
+        );
+    }
+
+    #[test]
+    fn lex_pop_default_mode() {
+        let lexer: Lexer<Kind> = Lexer::default()
+            .with_mode_rule(
+                DEFAULT_MODE,
+                Rule::new(Kind::RBrace, literal!("}")).with_pop(),
+            )
+            .with_rule(Kind::Ident, ident);
+        let tokens: Vec<Token<Kind>> = lexer.lex("}}a");
+
+        assert_eq!(tokens[0].kind(), Kind::RBrace);
+        assert_eq!(tokens[1].kind(), Kind::RBrace);
+        assert_eq!(tokens[2].kind(), Kind::Ident);
+    }
+
+    #[test]
+    fn lex_longest_match() {
+        let first: Lexer<Kind> = Lexer::default()
+            .with_rule(Kind::Eq, literal!("="))
+            .with_rule(Kind::Semi, literal!("=="))
+            .with_rule(Kind::Ident, literal!("=="));
+        let longest: Lexer<Kind> = first.clone().with_strategy(MatchStrategy::LongestMatch);
+
+        let tokens: Vec<Token<Kind>> = first.lex("==");
+        assert_eq!(tokens[0].kind(), Kind::Eq);
+        assert_eq!(tokens[1].kind(), Kind::Eq);
+
+        let tokens: Vec<Token<Kind>> = longest.lex("===");
+        assert_eq!(tokens[0].kind(), Kind::Semi);
+        assert_eq!(tokens[0].span(), Span::new(0, 2));
+        assert_eq!(tokens[1].kind(), Kind::Eq);
+        assert_eq!(tokens[2].kind(), Kind::EndOfFile);
+    }
+
+    #[test]
+    fn lex_literal_trie() {
+        let literals: &[(Kind, Literal)] = &[
+            (Kind::Eq, Literal::new("=")),
+            (Kind::Semi, Literal::new("==")),
+            (Kind::LBrace, Literal::keyword("if")),
+            (Kind::RBrace, Literal::new("ifx")),
+            (Kind::Int, Literal::new("=>")),
+            (Kind::Semi, Literal::keyword("i")),
+            (Kind::RBrace, Literal::keyword("IFY").with_ignore_case()),
+            (Kind::Eq, Literal::new("=>X").with_ignore_case()),
+        ];
+        let mut trie: Lexer<Kind> = Lexer::default().with_rule(Kind::Whitespace, whitespace);
+        let mut linear: Lexer<Kind> = trie.clone();
+        for (kind, literal) in literals {
+            trie.add_rule(*kind, literal.clone());
+            let literal: Literal = literal.clone();
+            linear.add_rule(*kind, move |source: &str| literal.try_match(source));
+        }
+        trie.add_rule(Kind::Ident, ident);
+        linear.add_rule(Kind::Ident, ident);
+
+        let sources: &[&str] = &[
+            "== = => ===",
+            "if ifx iff i ix",
+            "if=i==ifx",
+            "",
+            "@ifx",
+            "ify IfY IF =>x =>X",
+        ];
+        for strategy in [MatchStrategy::FirstMatch, MatchStrategy::LongestMatch] {
+            trie.set_strategy(strategy);
+            linear.set_strategy(strategy);
+            for source in sources {
+                assert_eq!(trie.lex(source), linear.lex(source), "source: {:?}", source);
+            }
+        }
+    }
+
+    #[test]
+    fn lex_macro_literal_trie() {
+        crate::lexer! {
+            #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+            enum LiteralKind {
+                Whitespace: whitespace,
+                EqEq: literal!("=="),
+                Eq: literal!("="),
+                If: keyword!("if"),
+                LBrace: literal!("{"),
+                Ident: ident,
+            }
+        }
+
+        let trie: Lexer<LiteralKind> = LiteralKind::lexer();
+        let mode: &Mode<LiteralKind> = &trie.modes[0];
+        assert_eq!(mode.segments.len(), 3);
+        assert!(matches!(mode.segments[1], Segment::Literals(_)));
+        assert_eq!(mode.rules[4].first_bytes(), ByteSet::EMPTY.with_byte(b'{'));
+        assert!(mode.dispatch[b'{' as usize].contains(&1));
+        assert!(!mode.dispatch[b'x' as usize].contains(&1));
+
+        let linear: Lexer<LiteralKind> = Lexer::default()
+            .with_rule(LiteralKind::Whitespace, whitespace)
+            .with_rule(LiteralKind::EqEq, literal!("=="))
+            .with_rule(LiteralKind::Eq, literal!("="))
+            .with_rule(LiteralKind::If, keyword!("if"))
+            .with_rule(LiteralKind::LBrace, literal!("{"))
+            .with_rule(LiteralKind::Ident, ident);
+        assert_eq!(linear.modes[0].segments.len(), 6);
+
+        let sources: &[&str] = &["", "if iff i", "===={=", "if{x==if}", "@if"];
+        for source in sources {
+            assert_eq!(trie.lex(source), linear.lex(source), "source: {:?}", source);
+        }
+    }
+
+    #[test]
+    fn lex_first_bytes() {
+        let any = |source: &str| source.chars().next().map(char::len_utf8);
+        let lexer: Lexer<Kind> = Lexer::default()
+            .with_mode_rule(
+                DEFAULT_MODE,
+                Rule::new(Kind::Int, any).with_first_bytes(ByteSet::ASCII_DIGIT),
+            )
+            .with_rule(Kind::Semi, literal!(";"))
+            .with_rule(Kind::Ident, any);
+        let source: &str = "1a;é";
+        let tokens: Vec<Token<Kind>> = lexer.lex(source);
+
+        let expected: &[(Kind, &str)] = &[
+            (Kind::Int, "1"),
+            (Kind::Ident, "a"),
+            (Kind::Semi, ";"),
+            (Kind::Ident, "é"),
+            (Kind::EndOfFile, ""),
+        ];
+
+        assert_eq!(tokens.len(), expected.len());
+        for (token, (kind, text)) in tokens.iter().zip(expected) {
+            assert_eq!(token.kind(), *kind);
+            assert_eq!(token.text(source), *text);
+        }
+    }
+
+    #[test]
+    fn tokens() {
+        let lexer: Lexer<Kind> = Kind::lexer();
+        let source: &str = "let x = 42; @";
+
+        let mut tokens = lexer.tokens(source);
+        assert_eq!(tokens.next().map(|t| t.kind()), Some(Kind::Ident));
+        assert_eq!(tokens.next().map(|t| t.kind()), Some(Kind::Whitespace));
+        assert_eq!(tokens.pos(), 4);
+
+        let tokens: Vec<Token<Kind>> = lexer.tokens(source).collect();
+        assert_eq!(tokens, lexer.lex(source));
+        assert_eq!(tokens.last().map(|t| t.kind()), Some(Kind::EndOfFile));
+
+        let mut tokens = lexer.tokens("");
+        assert_eq!(tokens.next().map(|t| t.kind()), Some(Kind::EndOfFile));
+        assert_eq!(tokens.next(), None);
+        assert_eq!(tokens.next(), None);
+    }
+
+    #[test]
+    fn relex() {
+        let lexer: Lexer<Kind> = Kind::lexer();
+        let test_cases: &[(&str, Span, &str)] = &[
+            ("let x = 42;", Span::new(4, 1), "value"),
+            ("let x = 42;", Span::new(8, 2), "7"),
+            ("let x = 42;", Span::new(3, 1), ""),
+            ("let x = 42;", Span::new(11, 0), " y"),
+            ("let x = 42;", Span::new(0, 0), "@"),
+            ("let x = 42;", Span::new(0, 11), ""),
+            ("a b c d e f", Span::new(5, 1), "ccc"),
+            ("a b c d e f", Span::new(2, 0), "1"),
+            ("{ab}{cd}", Span::new(4, 1), ""),
+            ("", Span::new(0, 0), "x = 1"),
+        ];
+
+        for (old_source, span, text) in test_cases {
+            let old_tokens: Vec<Token<Kind>> = lexer.lex(old_source);
+            let mut source: String = old_source.to_string();
+            source.replace_range(span.offset() as usize..span.end() as usize, text);
+
+            let tokens: Vec<Token<Kind>> =
+                lexer.relex(&old_tokens, Edit::new(*span, text), &source);
+            assert_eq!(tokens, lexer.lex(&source), "source: {:?}", source);
+        }
+    }
+
+    #[test]
+    fn relex_modes() {
+        let attempts: Arc<AtomicUsize> = Arc::default();
+        let counter: Arc<AtomicUsize> = attempts.clone();
+        let lexer: Lexer<Kind> = Lexer::default()
+            .with_mode_rule(
+                DEFAULT_MODE,
+                Rule::new(Kind::LBrace, literal!("{")).with_push("block"),
+            )
+            .with_rule(Kind::Whitespace, whitespace)
+            .with_rule(Kind::Ident, move |source: &str| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                ident(source)
+            })
+            .with_mode_rule("block", Rule::new(Kind::RBrace, literal!("}")).with_pop())
+            .with_mode_rule(
+                "block",
+                Rule::new(Kind::LBrace, literal!("{")).with_push("block"),
+            )
+            .with_mode_rule("block", Rule::new(Kind::Whitespace, whitespace))
+            .with_mode_rule("block", Rule::new(Kind::Int, digits));
+        let test_cases: &[(&str, Span, &str)] = &[
+            ("a {1 2} b", Span::new(4, 1), "33"),
+            ("a {1 2} b", Span::new(2, 1), ""),
+            ("a {1 2} b", Span::new(6, 1), ""),
+            ("a {1 2} b", Span::new(0, 0), "{"),
+            ("a {1 {2}} b", Span::new(8, 1), " "),
+            ("a {1 {2}} b", Span::new(5, 0), "}"),
+            ("a {1} b {2} c", Span::new(12, 1), "dd"),
+            ("", Span::new(0, 0), "{1}"),
+        ];
+
+        for (old_source, span, text) in test_cases {
+            let (old_tokens, old_stacks) = lexer.lex_modes(old_source);
+            let mut source: String = old_source.to_string();
+            source.replace_range(span.offset() as usize..span.end() as usize, text);
+
+            let edit: Edit = Edit::new(*span, text);
+            let relexed: (Vec<Token<Kind>>, ModeStacks) =
+                lexer.relex_modes(&old_tokens, &old_stacks, edit, &source);
+            assert_eq!(relexed, lexer.lex_modes(&source), "source: {:?}", source);
+            assert_eq!(lexer.relex(&old_tokens, edit, &source), relexed.0);
+        }
+
+        let source: String = format!("{} {{1}}", "a ".repeat(100));
+        let (tokens, stacks) = lexer.lex_modes(&source);
+        let mut edited: String = source.clone();
+        edited.insert(source.len() - 2, '2');
+        attempts.store(0, Ordering::Relaxed);
+        let edit: Edit = Edit::new(Span::new(source.len() as u32 - 2, 0), "2");
+        let (relexed, _) = lexer.relex_modes(&tokens, &stacks, edit, &edited);
+        assert!(attempts.load(Ordering::Relaxed) < 5);
+        assert_eq!(relexed, lexer.lex(&edited));
+    }
+
+    #[test]
+    fn lex_guards() {
+        let lexer: Lexer<Kind> = Lexer::default()
+            .with_trivia(Kind::Whitespace)
+            .with_rule(Kind::Whitespace, whitespace)
+            .with_rule(Kind::Ident, ident)
+            .with_rule(Kind::Eq, literal!("="))
+            .with_mode_rule(
+                DEFAULT_MODE,
+                Rule::new(Kind::Str, literal!("{"))
+                    .with_guard(|previous| previous == Some(Kind::Eq)),
+            )
+            .with_rule(Kind::LBrace, literal!("{"));
+        let source: &str = "x = {\n{ = {{";
+        let tokens: Vec<Token<Kind>> = lexer.lex(source);
+
+        let kinds: Vec<Kind> = tokens.iter().map(|t| t.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                Kind::Ident,
+                Kind::Whitespace,
+                Kind::Eq,
+                Kind::Whitespace,
+                Kind::Str,
+                Kind::Whitespace,
+                Kind::LBrace,
+                Kind::Whitespace,
+                Kind::Eq,
+                Kind::Whitespace,
+                Kind::Str,
+                Kind::LBrace,
+                Kind::EndOfFile,
+            ]
+        );
+
+        let test_cases: &[(Span, &str)] = &[
+            (Span::new(2, 1), "y"),
+            (Span::new(6, 0), "="),
+            (Span::new(3, 0), " \n "),
+            (Span::new(11, 1), ""),
+        ];
+        for (span, text) in test_cases {
+            let mut edited: String = source.to_string();
+            edited.replace_range(span.offset() as usize..span.end() as usize, text);
+            let relexed: Vec<Token<Kind>> = lexer.relex(&tokens, Edit::new(*span, text), &edited);
+            assert_eq!(relexed, lexer.lex(&edited), "source: {:?}", edited);
+        }
+    }
+
+    #[test]
+    fn lex_keywords() {
+        crate::lexer! {
+            #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+            enum KeywordKind {
+                Whitespace: whitespace,
+                Ident: ident => keywords { Let: "let", Fn: "fn" },
+                Hash: literal!("#") => push(directive),
+                Define,
+            }
+            mode directive {
+                Ident: ident => keywords { Define: "define" } => pop,
+            }
+        }
+
+        let lexer: Lexer<KeywordKind> = KeywordKind::lexer();
+        let source: &str = "let fn lets f #define define";
+        let tokens: Vec<Token<KeywordKind>> = lexer.lex(source);
+
+        let expected: &[(KeywordKind, &str)] = &[
+            (KeywordKind::Let, "let"),
+            (KeywordKind::Whitespace, " "),
+            (KeywordKind::Fn, "fn"),
+            (KeywordKind::Whitespace, " "),
+            (KeywordKind::Ident, "lets"),
+            (KeywordKind::Whitespace, " "),
+            (KeywordKind::Ident, "f"),
+            (KeywordKind::Whitespace, " "),
+            (KeywordKind::Hash, "#"),
+            (KeywordKind::Define, "define"),
+            (KeywordKind::Whitespace, " "),
+            (KeywordKind::Ident, "define"),
+            (KeywordKind::EndOfFile, ""),
+        ];
+
+        assert_eq!(tokens.len(), expected.len());
+        for (token, (kind, text)) in tokens.iter().zip(expected) {
+            assert_eq!(token.kind(), *kind);
+            assert_eq!(token.text(source), *text);
+        }
+        assert_eq!(KeywordKind::lexer().lex(source), tokens);
+    }
+
+    #[test]
+    fn lex_values() {
+        let lexer: Lexer<Kind> = Lexer::default()
+            .with_rule(Kind::Whitespace, whitespace)
+            .with_mode_rule(
+                DEFAULT_MODE,
+                Rule::new(Kind::Ident, ident).with_decoder(symbol),
+            )
+            .with_mode_rule(DEFAULT_MODE, Rule::new(Kind::Int, digits).with_decoder(int));
+        let source: &str = "x 42 x 340282366920938463463374607431768211456";
+        let (tokens, values): (Vec<Token<Kind>>, Values) = lexer.lex_values(source);
+
+        assert_eq!(tokens, lexer.lex(source));
+        assert_eq!(values.len(), 3);
+        let x: Symbol = values.get(tokens[0].span()).unwrap().as_symbol().unwrap();
+        assert_eq!(values.resolve(x), "x");
+        assert_eq!(values.get(tokens[2].span()), Some(&Value::Int(42)));
+        assert_eq!(values.get(tokens[4].span()), Some(&Value::Symbol(x)));
+        assert_eq!(values.get(tokens[6].span()), None);
+        assert_eq!(values.get(tokens[1].span()), None);
+    }
+
+    #[test]
+    fn lex_trivia() {
+        let lexer: Lexer<Kind> = Lexer::default()
+            .with_rule(Kind::Whitespace, whitespace)
+            .with_rule(Kind::LBrace, line_comment!("#"))
+            .with_rule(Kind::Ident, ident)
+            .with_rule(Kind::Semi, literal!(";"))
+            .with_trivia(Kind::Whitespace)
+            .with_trivia(Kind::LBrace);
+        let source: &str = "# head\na; # tail\n  # lead\n  b ;\n\n  # end";
+        let trivia: TriviaTokens<Kind> = lexer.lex_trivia(source);
+        let texts = |tokens: &[Token<Kind>]| -> Vec<String> {
+            tokens.iter().map(|t| t.text(source).to_string()).collect()
+        };
+
+        assert_eq!(texts(trivia.tokens()), ["a", ";", "b", ";", ""]);
+        assert_eq!(texts(trivia.leading(0)), ["# head\n"]);
+        assert!(trivia.trailing(0).is_empty());
+        assert!(trivia.leading(1).is_empty());
+        assert_eq!(texts(trivia.trailing(1)), [" ", "# tail\n"]);
+        assert_eq!(texts(trivia.leading(2)), ["  ", "# lead\n", "  "]);
+        assert_eq!(texts(trivia.trailing(2)), [" "]);
+        assert!(trivia.trailing(3).is_empty());
+        assert_eq!(texts(trivia.leading(4)), ["\n\n  ", "# end"]);
+        assert_eq!(trivia.trivia().len(), 9);
+    }
+
+    #[test]
+    fn lex_coalesce_unrecognized() {
+        let lexer: Lexer<Kind> = Kind::lexer().with_unrecognized(UnrecognizedPolicy::Coalesce);
+        let source: &str = "x @#é y @";
+        let (tokens, errors): (Vec<Token<Kind>>, Vec<ParseError>) = lexer.lex_diagnostics(source);
+
+        let expected: &[(Kind, &str)] = &[
+            (Kind::Ident, "x"),
+            (Kind::Whitespace, " "),
+            (Kind::Unrecognized, "@#é"),
+            (Kind::Whitespace, " "),
+            (Kind::Ident, "y"),
+            (Kind::Whitespace, " "),
+            (Kind::Unrecognized, "@"),
+            (Kind::EndOfFile, ""),
+        ];
+
+        assert_eq!(tokens.len(), expected.len());
+        for (token, (kind, text)) in tokens.iter().zip(expected) {
+            assert_eq!(token.kind(), *kind);
+            assert_eq!(token.text(source), *text);
+        }
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].span(), Span::new(2, 4));
+        assert_eq!(
+            errors[0].message(),
+            "3 unexpected characters starting with '@'"
+        );
+        assert_eq!(errors[1].span(), Span::new(9, 1));
+        assert_eq!(errors[1].message(), "unexpected character '@'");
+
+        let (_, errors): (Vec<Token<Kind>>, Vec<ParseError>) = Kind::lexer().lex_diagnostics("@@");
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn lex_unterminated_string() {
+        let lexer: Lexer<Kind> = Lexer::default()
+            .with_rule(Kind::Whitespace, whitespace)
+            .with_rule(Kind::Str, StringLiteral::default());
+        let source: &str = "\"ok\" \"open\n\"";
+        let (tokens, errors): (Vec<Token<Kind>>, Vec<ParseError>) = lexer.lex_diagnostics(source);
+
+        assert_eq!(tokens[2].text(source), "\"open");
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].span(), Span::new(5, 5));
+        assert_eq!(errors[0].message(), "unterminated string");
+        assert_eq!(errors[1].span(), Span::new(11, 1));
+    }
+
+    #[test]
+    fn lex_deferred_bodies() {
+        crate::lexer! {
+            #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+            enum ShellKind {
+                Whitespace: whitespace,
+                Word: ident,
+                Pipe: literal!("|"),
+                Comma: literal!(","),
+                Heredoc: Heredoc::default(),
+            }
+        }
+
+        let lexer: Lexer<ShellKind> = ShellKind::lexer();
+        let source: &str = "cat <<A | f <<B, x\na\nA\nb\nB\nls <<C\nC";
+        let expected: &[&str] = &[
+            "cat", " ", "<<A", " ", "|", " ", "f", " ", "<<B", ",", " ", "x", "\n", "a\nA", "\n",
+            "b\nB", "\n", "ls", " ", "<<C\nC", "",
+        ];
+        let tokens: Vec<Token<ShellKind>> = lexer.lex(source);
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text(source)).collect();
+        assert_eq!(texts, expected);
+        assert_eq!(tokens[13].kind(), ShellKind::Heredoc);
+        assert_eq!(tokens[15].kind(), ShellKind::Heredoc);
+
+        for chunk_size in 4..=8 {
+            let mut stream = lexer.stream(source.as_bytes()).with_chunk_size(chunk_size);
+            let mut texts: Vec<String> = Vec::default();
+            while stream.next_token().unwrap().is_some() {
+                texts.push(stream.text().to_string());
+            }
+            assert_eq!(texts, expected, "chunk size: {chunk_size}");
+        }
+
+        let edit: Edit = Edit::new(Span::new(9, 1), "");
+        let mut edited: String = source.to_string();
+        edited.replace_range(9..10, "");
+        assert_eq!(lexer.relex(&tokens, edit, &edited), lexer.lex(&edited));
+
+        let source: &str = "f <<EOF x\nbody";
+        let (tokens, errors): (Vec<Token<ShellKind>>, Vec<ParseError>) =
+            lexer.lex_diagnostics(source);
+        assert_eq!(tokens[6].text(source), "body");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span(), Span::new(10, 4));
+        assert_eq!(errors[0].message(), "unterminated heredoc");
+    }
+
     #[test]
     fn lex_spans() {
         let lexer: Lexer<Kind> = Kind::lexer();
@@ -0,0 +1,98 @@
+use crate::lexer::matchers::Literal;
+use crate::lexer::{MatchStrategy, Matcher};
+
+/// A byte trie over a run of consecutive literal rules.
+///
+/// Matching walks the trie once instead of trying each literal in turn and selects the same rule
+/// a linear scan over the run would select. Edges are keyed by ASCII-lowercased bytes so literals
+/// that ignore case share the trie; terminals verify the exact match.
+#[derive(Clone, Debug)]
+pub(in crate::lexer) struct LiteralTrie {
+    nodes: Vec<Node>,
+}
+
+/// A trie node.
+#[derive(Clone, Debug, Default)]
+struct Node {
+    /// The child nodes sorted by ASCII-lowercased byte.
+    children: Vec<(u8, usize)>,
+
+    /// The literals ending at this node as `(rule_index, literal)` sorted by rule index.
+    terminals: Vec<(usize, Literal)>,
+}
+
+impl Default for LiteralTrie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+        }
+    }
+}
+
+impl LiteralTrie {
+    //! Building
+
+    /// Inserts the `literal` for the rule at `rule_index`.
+    ///
+    /// Rule indices must be inserted in increasing order.
+    pub(in crate::lexer) fn insert(&mut self, rule_index: usize, literal: &Literal) {
+        let mut node: usize = 0;
+        for byte in literal.text().bytes().map(|b| b.to_ascii_lowercase()) {
+            node = match self.nodes[node]
+                .children
+                .binary_search_by_key(&byte, |(b, _)| *b)
+            {
+                Ok(i) => self.nodes[node].children[i].1,
+                Err(i) => {
+                    self.nodes.push(Node::default());
+                    let child: usize = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(i, (byte, child));
+                    child
+                }
+            };
+        }
+        if node != 0 {
+            self.nodes[node]
+                .terminals
+                .push((rule_index, literal.clone()));
+        }
+    }
+}
+
+impl LiteralTrie {
+    //! Matching
+
+    /// Matches the start of the `source`. Returns the `(rule_index, len)` of the selected rule.
+    pub(in crate::lexer) fn find(
+        &self,
+        source: &str,
+        strategy: MatchStrategy,
+    ) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+        let mut node: usize = 0;
+        for (i, byte) in source.bytes().map(|b| b.to_ascii_lowercase()).enumerate() {
+            let children: &[(u8, usize)] = &self.nodes[node].children;
+            node = match children.binary_search_by_key(&byte, |(b, _)| *b) {
+                Ok(c) => children[c].1,
+                Err(_) => break,
+            };
+            let len: usize = i + 1;
+            let found: Option<usize> = self.nodes[node]
+                .terminals
+                .iter()
+                .find(|(_, literal)| literal.try_match(source).is_some())
+                .map(|(rule_index, _)| *rule_index);
+            if let Some(rule_index) = found {
+                best = match (strategy, best) {
+                    (MatchStrategy::FirstMatch, Some((best_index, _)))
+                        if best_index < rule_index =>
+                    {
+                        best
+                    }
+                    _ => Some((rule_index, len)),
+                };
+            }
+        }
+        best
+    }
+}
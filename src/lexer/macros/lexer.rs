@@ -3,8 +3,26 @@
 /// Automatically adds `Unrecognized` and `EndOfFile` variants to the enum, implements the
 /// [TokenKind] trait, and generates a `lexer()` method that builds a [Lexer] from the rules.
 ///
+/// Variants declared with a matcher are rules of the default mode. Variants declared without a
+/// matcher only add the token kind. Each `mode` block adds rules for existing variants to a named
+/// mode. A rule can be followed by `=> push(mode)`, `=> pop`, or `=> switch(mode)` to change the
+/// active mode when it matches, by `=> first_bytes(set)` to declare the [ByteSet] its matches can
+/// start with, and by `=> value(decoder)` to decode token values with a [Decoder]. A rule in the
+/// enum can be followed by `=> keywords { Variant: "text", ... }` to classify its matches with
+/// [Keywords]; the keyword variants are added to the enum. A `mode` rule takes the same action with
+/// variants already declared in the enum. Each keyword table is built once and shared by every
+/// `lexer()` call.
+///
+/// `literal!` and `keyword!` rules are built as [Literal] matchers, so runs of them are merged into
+/// a single trie and declare their first byte.
+///
+/// A rule can be guarded by the kind of the previous significant token with
+/// `=> after(Variant, ...)`, `=> not_after(Variant, ...)` or `=> guard(fn)`. Trivia kinds are
+/// skipped when finding the previous token; add them with `Lexer::with_trivia`.
+///
 /// # Example
 /// ```
+/// use lex::lexer::ByteSet;
 /// use lex::lexer::matchers::{digits, ident, whitespace};
 /// use lex::{keyword, lexer, line_comment, literal};
 ///
@@ -12,10 +30,10 @@
 ///     #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 ///     pub enum Kind {
 ///         LineComment : line_comment!("//"),
-///         Whitespace : whitespace,
+///         Whitespace : whitespace => first_bytes(ByteSet::ASCII_WHITESPACE),
 ///         Import : keyword!("import"),
-///         Ident : ident,
-///         Integer : digits,
+///         Ident : ident => first_bytes(ByteSet::IDENT_START),
+///         Integer : digits => first_bytes(ByteSet::ASCII_DIGIT),
 ///         LBrace : literal!("{"),
 ///     }
 /// }
@@ -27,17 +45,138 @@
 /// assert_eq!(tokens[4].kind(), Kind::LBrace);
 /// assert_eq!(tokens[5].kind(), Kind::EndOfFile);
 /// ```
+///
+/// # Keywords
+/// ```
+/// use lex::lexer::matchers::{ident, whitespace};
+/// use lex::lexer;
+///
+/// lexer! {
+///     #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+///     pub enum Kind {
+///         Whitespace : whitespace,
+///         Ident : ident => keywords { If: "if", Else: "else" },
+///     }
+/// }
+///
+/// let tokens = Kind::lexer().lex("if x else y");
+/// let kinds: Vec<Kind> = tokens.iter().map(|t| t.kind()).collect();
+/// assert_eq!(
+///     kinds,
+///     [
+///         Kind::If,
+///         Kind::Whitespace,
+///         Kind::Ident,
+///         Kind::Whitespace,
+///         Kind::Else,
+///         Kind::Whitespace,
+///         Kind::Ident,
+///         Kind::EndOfFile,
+///     ]
+/// );
+/// ```
+///
+/// # Guards
+/// ```
+/// use lex::lexer::matchers::{digits, ident, whitespace};
+/// use lex::{lexer, literal, pattern};
+///
+/// lexer! {
+///     #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+///     pub enum Kind {
+///         Whitespace : whitespace,
+///         Ident : ident,
+///         Number : digits,
+///         RParen : literal!(")"),
+///         Regex : pattern!("/[^/\\n]+/") => not_after(Ident, Number, RParen),
+///         Slash : literal!("/"),
+///     }
+/// }
+///
+/// let lexer = Kind::lexer().with_trivia(Kind::Whitespace);
+/// let kinds = |source: &str| -> Vec<Kind> {
+///     let tokens = lexer.lex(source);
+///     tokens.iter().map(|t| t.kind()).filter(|k| *k != Kind::Whitespace).collect()
+/// };
+/// assert_eq!(kinds("a / b / c")[1], Kind::Slash);
+/// assert_eq!(kinds("f /b/")[1], Kind::Slash);
+/// assert_eq!(kinds("/b/ + 1")[0], Kind::Regex);
+/// ```
+///
+/// # Modes
+/// ```
+/// use lex::lexer::matchers::ident;
+/// use lex::{lexer, literal};
+///
+/// lexer! {
+///     #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+///     pub enum Kind {
+///         Quote : literal!("\"") => push(string),
+///         Ident : ident,
+///         Text,
+///         InterpStart,
+///         InterpEnd,
+///     }
+///
+///     mode string {
+///         Quote : literal!("\"") => pop,
+///         InterpStart : literal!("${") => push(interp),
+///         Text : |s: &str| s.find(['"', '$']).or(Some(s.len())),
+///     }
+///
+///     mode interp {
+///         InterpEnd : literal!("}") => pop,
+///         Ident : ident,
+///     }
+/// }
+///
+/// let tokens = Kind::lexer().lex("\"a ${b} c\"");
+/// let kinds: Vec<Kind> = tokens.iter().map(|t| t.kind()).collect();
+/// assert_eq!(
+///     kinds,
+///     [
+///         Kind::Quote,
+///         Kind::Text,
+///         Kind::InterpStart,
+///         Kind::Ident,
+///         Kind::InterpEnd,
+///         Kind::Text,
+///         Kind::Quote,
+///         Kind::EndOfFile,
+///     ]
+/// );
+/// ```
 #[macro_export]
 macro_rules! lexer {
     (
         $(#[$meta:meta])*
         $vis:vis enum $name:ident {
-            $($variant:ident : $matcher:expr),* $(,)?
+            $(
+                $variant:ident
+                $(
+                    : $matcher:expr
+                    $(
+                        => $action:ident $(($($arg:tt)*))?
+                        $({ $($keyword:ident : $keyword_text:literal),* $(,)? })?
+                    )*
+                )?
+            ),* $(,)?
         }
+        $(
+            mode $mode:ident {
+                $(
+                    $mode_variant:ident : $mode_matcher:expr
+                    $(
+                        => $mode_action:ident $(($($mode_arg:tt)*))?
+                        $({ $($mode_keyword:ident : $mode_keyword_text:literal),* $(,)? })?
+                    )*
+                ),* $(,)?
+            }
+        )*
     ) => {
         $(#[$meta])*
         $vis enum $name {
-            $($variant,)*
+            $($variant, $($($($($keyword,)*)?)*)?)*
             Unrecognized,
             EndOfFile,
         }
@@ -57,9 +196,110 @@ macro_rules! lexer {
 
             /// Creates a [Lexer] with rules in the order they were declared.
             pub fn lexer() -> $crate::lexer::Lexer<$name> {
+                // resolves the matchers with the caller's macros, so their imports stay used
+                let _ = || {
+                    $($(let _ = $matcher;)?)*
+                    $($(let _ = $mode_matcher;)*)*
+                };
+
+                // declared `literal!` and `keyword!` rules become `Literal` matchers, so runs of
+                // them are merged into a trie and declare their first byte
+                #[allow(unused_macros)]
+                macro_rules! literal {
+                    ($literal_text:literal) => {
+                        $crate::lexer::matchers::Literal::new($literal_text)
+                    };
+                }
+                #[allow(unused_macros)]
+                macro_rules! keyword {
+                    ($literal_text:literal) => {
+                        $crate::lexer::matchers::Literal::keyword($literal_text)
+                    };
+                }
+
                 $crate::lexer::Lexer::default()
-                    $(.with_rule($name::$variant, $matcher))*
+                    $($(.with_mode_rule(
+                        $crate::lexer::DEFAULT_MODE,
+                        $crate::__lexer_rule!(
+                            $name::$variant, $matcher
+                            $(
+                                , $action $(($($arg)*))?
+                                $({ $name; $($name::$keyword : $keyword_text),* })?
+                            )*
+                        ),
+                    ))?)*
+                    $($(.with_mode_rule(
+                        stringify!($mode),
+                        $crate::__lexer_rule!(
+                            $name::$mode_variant, $mode_matcher
+                            $(
+                                , $mode_action $(($($mode_arg)*))?
+                                $({ $name; $($name::$mode_keyword : $mode_keyword_text),* })?
+                            )*
+                        ),
+                    ))*)*
             }
         }
     };
 }
+
+/// Builds a [Rule] from a `lexer!` rule declaration by applying its actions in order. (internal)
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __lexer_rule {
+    (@rule $rule:expr) => {
+        $rule
+    };
+    (@rule $rule:expr, push($mode:ident) $($rest:tt)*) => {
+        $crate::__lexer_rule!(@rule $rule.with_push(stringify!($mode)) $($rest)*)
+    };
+    (@rule $rule:expr, pop $($rest:tt)*) => {
+        $crate::__lexer_rule!(@rule $rule.with_pop() $($rest)*)
+    };
+    (@rule $rule:expr, switch($mode:ident) $($rest:tt)*) => {
+        $crate::__lexer_rule!(@rule $rule.with_switch(stringify!($mode)) $($rest)*)
+    };
+    (@rule $rule:expr, first_bytes($set:expr) $($rest:tt)*) => {
+        $crate::__lexer_rule!(@rule $rule.with_first_bytes($set) $($rest)*)
+    };
+    (@rule $rule:expr, value($decoder:expr) $($rest:tt)*) => {
+        $crate::__lexer_rule!(@rule $rule.with_decoder($decoder) $($rest)*)
+    };
+    (@rule $rule:expr, guard($guard:expr) $($rest:tt)*) => {
+        $crate::__lexer_rule!(@rule $rule.with_guard($guard) $($rest)*)
+    };
+    (@rule $rule:expr, after($($kind:ident),+ $(,)?) $($rest:tt)*) => {
+        $crate::__lexer_rule!(
+            @rule $rule.with_guard(|previous: Option<Self>| {
+                matches!(previous, Some($(Self::$kind)|+))
+            })
+            $($rest)*
+        )
+    };
+    (@rule $rule:expr, not_after($($kind:ident),+ $(,)?) $($rest:tt)*) => {
+        $crate::__lexer_rule!(
+            @rule $rule.with_guard(|previous: Option<Self>| {
+                !matches!(previous, Some($(Self::$kind)|+))
+            })
+            $($rest)*
+        )
+    };
+    (@rule $rule:expr, keywords { $ty:ty; $($kind:path : $text:literal),* } $($rest:tt)*) => {
+        $crate::__lexer_rule!(
+            @rule $rule.with_keywords({
+                static KEYWORDS: ::std::sync::OnceLock<
+                    ::std::sync::Arc<$crate::lexer::Keywords<$ty>>,
+                > = ::std::sync::OnceLock::new();
+                KEYWORDS
+                    .get_or_init(|| {
+                        ::std::sync::Arc::new($crate::lexer::Keywords::new([$(($text, $kind)),*]))
+                    })
+                    .clone()
+            })
+            $($rest)*
+        )
+    };
+    ($kind:expr, $matcher:expr $(, $($rest:tt)*)?) => {
+        $crate::__lexer_rule!(@rule $crate::lexer::Rule::new($kind, $matcher) $(, $($rest)*)?)
+    };
+}
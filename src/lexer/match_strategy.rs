@@ -0,0 +1,10 @@
+/// A rule selection strategy. Decides which rule wins when several rules match.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum MatchStrategy {
+    /// The first declared rule that matches wins.
+    #[default]
+    FirstMatch,
+
+    /// The rule with the longest match wins. Declaration order only breaks ties. (maximal munch)
+    LongestMatch,
+}
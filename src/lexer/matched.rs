@@ -0,0 +1,29 @@
+use crate::lexer::{Interner, Matcher, Rule, Value};
+use std::sync::Arc;
+
+/// What matched a token: a rule, or the matcher of a body deferred by a rule.
+#[derive(Clone)]
+pub(in crate::lexer) enum Matched<'a, K> {
+    Rule(&'a Rule<K>),
+    Body(Arc<dyn Matcher>),
+}
+
+impl<K> Matched<'_, K> {
+    //! Values
+
+    /// Decodes the value of a token with the matched `text`. (`None` for bodies)
+    pub(in crate::lexer) fn decode(&self, text: &str, interner: &mut Interner) -> Option<Value> {
+        match self {
+            Self::Rule(rule) => rule.decode(text, interner),
+            Self::Body(_) => None,
+        }
+    }
+
+    /// Diagnoses the matched `text`. Returns an error message if the match is malformed.
+    pub(in crate::lexer) fn diagnose(&self, text: &str) -> Option<String> {
+        match self {
+            Self::Rule(rule) => rule.diagnose(text),
+            Self::Body(matcher) => matcher.diagnose(text),
+        }
+    }
+}
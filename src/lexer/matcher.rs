@@ -0,0 +1,124 @@
+use crate::lexer::ByteSet;
+use crate::lexer::matchers::Literal;
+use std::sync::Arc;
+
+/// A token matcher. Matches a prefix of the source text.
+///
+/// Implemented for fn pointers, closures, and boxed matchers so rules can be built from runtime
+/// configuration.
+pub trait Matcher: Send + Sync {
+    /// Attempts to match the start of the `source`. Returns the number of bytes consumed.
+    fn try_match(&self, source: &str) -> Option<usize>;
+
+    /// Gets the literal this matcher is equivalent to, if any.
+    ///
+    /// Literal matchers can be merged with neighbouring literal rules by the [Lexer].
+    ///
+    /// [Lexer]: crate::lexer::Lexer
+    fn literal(&self) -> Option<&Literal> {
+        None
+    }
+
+    /// Gets the set of bytes every match can start with, if known.
+    ///
+    /// The [Lexer] only tries the matcher at positions starting with one of these bytes.
+    ///
+    /// [Lexer]: crate::lexer::Lexer
+    fn first_bytes(&self) -> Option<ByteSet> {
+        None
+    }
+
+    /// Diagnoses the matched `text`. Returns an error message if the match is malformed, like an
+    /// unterminated string.
+    ///
+    /// Reported by [Lexer::lex_diagnostics].
+    ///
+    /// [Lexer::lex_diagnostics]: crate::lexer::Lexer::lex_diagnostics
+    fn diagnose(&self, _text: &str) -> Option<String> {
+        None
+    }
+
+    /// Checks if matches may defer a body to the following lines. (see [Matcher::deferred_body])
+    fn defers_bodies(&self) -> bool {
+        false
+    }
+
+    /// Gets the matcher of the body the matched `text` defers to the following lines, if any. (e.g.
+    /// a heredoc whose opening line continues after the tag)
+    ///
+    /// The [Lexer] lexes the rest of the line first, then matches the body at the start of the
+    /// next line and emits it as a token of the rule kind.
+    ///
+    /// [Lexer]: crate::lexer::Lexer
+    fn deferred_body(&self, _text: &str) -> Option<Arc<dyn Matcher>> {
+        None
+    }
+}
+
+impl<F: Fn(&str) -> Option<usize> + Send + Sync> Matcher for F {
+    fn try_match(&self, source: &str) -> Option<usize> {
+        self(source)
+    }
+}
+
+impl Matcher for Box<dyn Matcher> {
+    fn try_match(&self, source: &str) -> Option<usize> {
+        (**self).try_match(source)
+    }
+
+    fn literal(&self) -> Option<&Literal> {
+        (**self).literal()
+    }
+
+    fn first_bytes(&self) -> Option<ByteSet> {
+        (**self).first_bytes()
+    }
+
+    fn diagnose(&self, text: &str) -> Option<String> {
+        (**self).diagnose(text)
+    }
+
+    fn defers_bodies(&self) -> bool {
+        (**self).defers_bodies()
+    }
+
+    fn deferred_body(&self, text: &str) -> Option<Arc<dyn Matcher>> {
+        (**self).deferred_body(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::Matcher;
+    use crate::lexer::matchers::digits;
+
+    #[test]
+    fn fn_pointer() {
+        let matcher: fn(&str) -> Option<usize> = digits;
+        assert_eq!(matcher.try_match("123abc"), Some(3));
+        assert_eq!(matcher.try_match("abc"), None);
+    }
+
+    #[test]
+    fn closure() {
+        let keywords: Vec<String> = vec!["let".to_string(), "fn".to_string()];
+        let matcher = move |source: &str| -> Option<usize> {
+            keywords
+                .iter()
+                .find(|keyword| source.starts_with(keyword.as_str()))
+                .map(|keyword| keyword.len())
+        };
+        assert_eq!(matcher.try_match("fn main"), Some(2));
+        assert_eq!(matcher.try_match("let x"), Some(3));
+        assert_eq!(matcher.try_match("x"), None);
+    }
+
+    #[test]
+    fn boxed() {
+        let delimiter: String = "#".to_string();
+        let matcher: Box<dyn Matcher> =
+            Box::new(move |source: &str| source.starts_with(&delimiter).then_some(1));
+        assert_eq!(matcher.try_match("# comment"), Some(1));
+        assert_eq!(matcher.try_match("comment"), None);
+    }
+}
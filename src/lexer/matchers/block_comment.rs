@@ -0,0 +1,186 @@
+use crate::lexer::{ByteSet, Matcher};
+
+/// A block comment matcher. Matches from the open delimiter through the matching close delimiter,
+/// optionally counting nested comments.
+///
+/// Unterminated comments match to the end of the source so they lex as a single token, and
+/// [Lexer::lex_diagnostics] reports them.
+///
+/// [Lexer::lex_diagnostics]: crate::lexer::Lexer::lex_diagnostics
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BlockComment {
+    open: String,
+    close: String,
+    nesting: bool,
+}
+
+impl BlockComment {
+    //! Construction
+
+    /// Creates a new block comment matcher with the `open` and `close` delimiters.
+    ///
+    /// # Panics
+    /// Panics if a delimiter is empty.
+    pub fn new(open: impl Into<String>, close: impl Into<String>) -> Self {
+        let open: String = open.into();
+        let close: String = close.into();
+        assert!(
+            !open.is_empty() && !close.is_empty(),
+            "block comment delimiters must not be empty"
+        );
+
+        Self {
+            open,
+            close,
+            nesting: false,
+        }
+    }
+
+    /// Makes nested comments need their own close delimiters. (builder pattern)
+    #[must_use]
+    pub fn with_nesting(mut self) -> Self {
+        self.nesting = true;
+        self
+    }
+}
+
+impl BlockComment {
+    //! Properties
+
+    /// Gets the open delimiter.
+    pub fn open(&self) -> &str {
+        &self.open
+    }
+
+    /// Gets the close delimiter.
+    pub fn close(&self) -> &str {
+        &self.close
+    }
+
+    /// Checks if nested comments are counted.
+    pub fn is_nesting(&self) -> bool {
+        self.nesting
+    }
+}
+
+impl BlockComment {
+    //! Matching
+
+    /// Scans the block comment at the start of the `source`. Returns the length and whether the
+    /// comment is terminated.
+    fn scan(&self, source: &str) -> Option<(usize, bool)> {
+        if !source.starts_with(self.open.as_str()) {
+            return None;
+        }
+        let mut depth: usize = 1;
+        let mut pos: usize = self.open.len();
+        while pos < source.len() {
+            let rest: &str = &source[pos..];
+            if rest.starts_with(self.close.as_str()) {
+                pos += self.close.len();
+                depth -= 1;
+                if depth == 0 {
+                    return Some((pos, true));
+                }
+            } else if self.nesting && rest.starts_with(self.open.as_str()) {
+                pos += self.open.len();
+                depth += 1;
+            } else {
+                pos += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        Some((source.len(), false))
+    }
+}
+
+impl Matcher for BlockComment {
+    fn try_match(&self, source: &str) -> Option<usize> {
+        self.scan(source).map(|(len, _)| len)
+    }
+
+    fn first_bytes(&self) -> Option<ByteSet> {
+        Some(ByteSet::EMPTY.with_byte(self.open.as_bytes()[0]))
+    }
+
+    fn diagnose(&self, text: &str) -> Option<String> {
+        self.scan(text)
+            .filter(|(_, terminated)| !terminated)
+            .map(|_| "unterminated block comment".to_string())
+    }
+}
+
+/// Matches a block comment from the open delimiter through the close delimiter.
+///
+/// Pass `nested` to count nested comments, as in Rust, Swift and Haskell.
+///
+/// # Example
+/// ```
+/// use lex::block_comment;
+/// use lex::lexer::Matcher;
+///
+/// let matcher = block_comment!("/*", "*/");
+/// assert_eq!(matcher.try_match("/* a /* b */ c */"), Some(12));
+///
+/// let matcher = block_comment!("{-", "-}", nested);
+/// assert_eq!(matcher.try_match("{- a {- b -} c -} x"), Some(17));
+/// ```
+#[macro_export]
+macro_rules! block_comment {
+    ($open:literal, $close:literal) => {
+        $crate::lexer::matchers::BlockComment::new($open, $close)
+    };
+    ($open:literal, $close:literal, nested) => {
+        $crate::lexer::matchers::BlockComment::new($open, $close).with_nesting()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::Matcher;
+
+    #[test]
+    fn fn_block_comment() {
+        let flat = block_comment!("/*", "*/");
+        let nested = block_comment!("/*", "*/", nested);
+
+        let test_cases: &[(&str, Option<usize>, Option<usize>)] = &[
+            ("", None, None),
+            ("/", None, None),
+            ("// x", None, None),
+            ("/**/", Some(4), Some(4)),
+            ("/*/", Some(3), Some(3)),
+            ("/* a */ b", Some(7), Some(7)),
+            ("/* a\n b */", Some(10), Some(10)),
+            ("/* é */", Some(8), Some(8)),
+            ("/* /* */ */", Some(8), Some(11)),
+            ("/* /* */", Some(8), Some(8)),
+        ];
+
+        for (source, expected_flat, expected_nested) in test_cases {
+            assert_eq!(
+                flat.try_match(source),
+                *expected_flat,
+                "source: {:?}",
+                source
+            );
+            assert_eq!(
+                nested.try_match(source),
+                *expected_nested,
+                "source: {:?}",
+                source
+            );
+        }
+
+        assert_eq!(flat.diagnose("/* a */"), None);
+        assert_eq!(
+            nested.diagnose("/* /* */").as_deref(),
+            Some("unterminated block comment")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "block comment delimiters must not be empty")]
+    fn fn_block_comment_empty() {
+        let _ = block_comment!("", "*/");
+    }
+}
@@ -0,0 +1,392 @@
+use crate::lexer::matchers::HeredocMatch;
+use crate::lexer::{ByteSet, Matcher};
+use std::sync::Arc;
+
+/// A heredoc matcher. Matches an opener and tag, like `<<EOF`, at the end of a line, the body
+/// lines, and the closing line holding only the tag.
+///
+/// Tags are identifiers (`[a-zA-Z_][a-zA-Z0-9_]*`) by default. The token ends after the closing
+/// tag, so its line ending lexes as the next token. The body span is available from
+/// [HeredocMatch::body_span].
+///
+/// When the opening line continues after the tag, like `cat <<EOF | grep x` or `f(<<EOF, x)`,
+/// the token ends after the tag. The [Lexer] lexes the rest of the line as usual, then the body
+/// lines and closing tag as a second token of the same kind. [Heredoc::scan] reports the whole
+/// heredoc in both forms.
+///
+/// Unterminated heredocs match to the end of the source so they lex as a single token, and
+/// [Lexer::lex_diagnostics] reports them.
+///
+/// # Example
+/// ```
+/// use lex::lexer::Matcher;
+/// use lex::lexer::matchers::Heredoc;
+///
+/// let heredoc: Heredoc = Heredoc::default().with_indentation();
+/// assert_eq!(heredoc.try_match("<<EOF\nhello\nEOF\nx"), Some(15));
+///
+/// let text: &str = "<<-END\n\tone\n\t two\n\tEND";
+/// let m = heredoc.scan(text).unwrap();
+/// assert_eq!(m.body(text), "one\n two\n");
+/// ```
+///
+/// [Lexer]: crate::lexer::Lexer
+/// [Lexer::lex_diagnostics]: crate::lexer::Lexer::lex_diagnostics
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Heredoc {
+    opener: String,
+    indentation: bool,
+    quoted_tags: bool,
+}
+
+impl Default for Heredoc {
+    fn default() -> Self {
+        Self::new("<<")
+    }
+}
+
+impl Heredoc {
+    //! Construction
+
+    /// Creates a new heredoc matcher with the `opener` before the tag.
+    pub fn new(opener: impl Into<String>) -> Self {
+        let opener: String = opener.into();
+        debug_assert!(!opener.is_empty());
+
+        Self {
+            opener,
+            indentation: false,
+            quoted_tags: false,
+        }
+    }
+}
+
+impl Heredoc {
+    //! Builders
+
+    /// Enables indented heredocs, like `<<-EOF`, whose closing tag may be indented with tabs.
+    /// (builder pattern)
+    ///
+    /// As in POSIX shells, [HeredocMatch::body] strips all leading tabs from their body lines.
+    #[must_use]
+    pub fn with_indentation(mut self) -> Self {
+        self.indentation = true;
+        self
+    }
+
+    /// Enables tags quoted with `'` or `"`, like `<<'EOF'`, which may hold any chars but the
+    /// quote. (builder pattern)
+    ///
+    /// The closing tag is written without the quotes.
+    #[must_use]
+    pub fn with_quoted_tags(mut self) -> Self {
+        self.quoted_tags = true;
+        self
+    }
+}
+
+impl Heredoc {
+    //! Matching
+
+    /// Scans the heredoc at the start of the `source`.
+    ///
+    /// If the opening line continues after the tag, the body starts on the next line and the
+    /// match includes the rest of the opening line.
+    pub fn scan(&self, source: &str) -> Option<HeredocMatch> {
+        let (tag_end, tag, indented, quoted): (usize, &str, bool, bool) =
+            self.scan_opener(source)?;
+        let body_offset: usize = source[tag_end..]
+            .find('\n')
+            .map_or(source.len(), |i| tag_end + i + 1);
+        let (body_len, len, terminated): (usize, usize, bool) =
+            Self::scan_body(&source[body_offset..], tag, indented);
+        Some(HeredocMatch::new(
+            body_offset + len,
+            body_offset,
+            body_len,
+            indented,
+            quoted,
+            terminated,
+        ))
+    }
+
+    /// Scans the opener and tag at the start of the `source`. Returns the end of the tag, the tag
+    /// without quotes, and whether the heredoc is indented and the tag quoted.
+    fn scan_opener<'a>(&self, source: &'a str) -> Option<(usize, &'a str, bool, bool)> {
+        let mut pos: usize = self.opener.len();
+        if !source.starts_with(self.opener.as_str()) {
+            return None;
+        }
+
+        let indented: bool = self.indentation && source[pos..].starts_with('-');
+        if indented {
+            pos += 1;
+        }
+
+        let (tag, quoted): (&str, bool) = self.scan_tag(&source[pos..])?;
+        pos += tag.len() + if quoted { 2 } else { 0 };
+        Some((pos, tag, indented, quoted))
+    }
+
+    /// Scans the tag at the start of the `source`. Returns the tag without quotes and whether it
+    /// was quoted.
+    fn scan_tag<'a>(&self, source: &'a str) -> Option<(&'a str, bool)> {
+        if self.quoted_tags
+            && let Some(quote) = source.chars().next().filter(|c| *c == '\'' || *c == '"')
+        {
+            let end: usize = source[1..].find([quote, '\n'])?;
+            let tag: &str = &source[1..1 + end];
+            return (!tag.is_empty() && source[1 + end..].starts_with(quote))
+                .then_some((tag, true));
+        }
+
+        let bytes: &[u8] = source.as_bytes();
+        if bytes.is_empty() || !(bytes[0].is_ascii_alphabetic() || bytes[0] == b'_') {
+            return None;
+        }
+        let len: usize = bytes
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+            .count();
+        Some((&source[..len], false))
+    }
+
+    /// Scans the body lines and closing tag at the start of the `source`. Returns the body length,
+    /// the length through the closing tag, and whether the closing tag was found.
+    ///
+    /// Unterminated bodies extend to the end of the `source`.
+    fn scan_body(source: &str, tag: &str, indented: bool) -> (usize, usize, bool) {
+        let mut line_start: usize = 0;
+        while line_start < source.len() {
+            let line_end: usize = source[line_start..]
+                .find('\n')
+                .map_or(source.len(), |i| line_start + i);
+            let line: &str = &source[line_start..line_end];
+            let line: &str = line.strip_suffix('\r').unwrap_or(line);
+            let close: &str = if indented {
+                line.trim_start_matches('\t')
+            } else {
+                line
+            };
+            if close == tag {
+                return (line_start, line_start + line.len(), true);
+            }
+            line_start = line_end + 1;
+        }
+        (source.len(), source.len(), false)
+    }
+
+    /// Checks if the opening line continues in the `rest` after the tag.
+    fn continues_line(rest: &str) -> bool {
+        let line: &str = rest.split('\n').next().unwrap();
+        !line.strip_suffix('\r').unwrap_or(line).is_empty()
+    }
+}
+
+impl Matcher for Heredoc {
+    fn try_match(&self, source: &str) -> Option<usize> {
+        let (tag_end, ..): (usize, &str, bool, bool) = self.scan_opener(source)?;
+        if Self::continues_line(&source[tag_end..]) {
+            return Some(tag_end);
+        }
+        self.scan(source).map(|m| m.len())
+    }
+
+    fn first_bytes(&self) -> Option<ByteSet> {
+        Some(ByteSet::EMPTY.with_byte(self.opener.as_bytes()[0]))
+    }
+
+    fn diagnose(&self, text: &str) -> Option<String> {
+        if self.deferred_body(text).is_some() {
+            return None;
+        }
+        self.scan(text)
+            .filter(|m| !m.is_terminated())
+            .map(|_| "unterminated heredoc".to_string())
+    }
+
+    fn defers_bodies(&self) -> bool {
+        true
+    }
+
+    fn deferred_body(&self, text: &str) -> Option<Arc<dyn Matcher>> {
+        let (tag_end, tag, indented, _): (usize, &str, bool, bool) = self.scan_opener(text)?;
+        let body: HeredocBody = HeredocBody {
+            tag: tag.to_string(),
+            indented,
+        };
+        (tag_end == text.len()).then(|| Arc::new(body) as Arc<dyn Matcher>)
+    }
+}
+
+/// The body of a heredoc whose opening line continues after the tag. Matches the body lines and
+/// the closing tag.
+struct HeredocBody {
+    tag: String,
+    indented: bool,
+}
+
+impl Matcher for HeredocBody {
+    fn try_match(&self, source: &str) -> Option<usize> {
+        Some(Heredoc::scan_body(source, &self.tag, self.indented).1)
+    }
+
+    fn diagnose(&self, text: &str) -> Option<String> {
+        let (_, _, terminated): (usize, usize, bool) =
+            Heredoc::scan_body(text, &self.tag, self.indented);
+        (!terminated).then(|| "unterminated heredoc".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_heredoc() {
+        let heredoc: Heredoc = Heredoc::default().with_indentation().with_quoted_tags();
+
+        let test_cases: &[(&str, Option<HeredocMatch>)] = &[
+            ("", None),
+            ("<", None),
+            ("<<", None),
+            ("<< EOF\nEOF", None),
+            ("<<1\n1", None),
+            ("<<'EOF\nEOF", None),
+            ("<<''\n", None),
+            (
+                "<<EOF\nEOF",
+                Some(HeredocMatch::new(9, 6, 0, false, false, true)),
+            ),
+            (
+                "<<EOF\na\nb\nEOF\nx",
+                Some(HeredocMatch::new(13, 6, 4, false, false, true)),
+            ),
+            (
+                "<<EOF\r\na\r\nEOF\r\n",
+                Some(HeredocMatch::new(13, 7, 3, false, false, true)),
+            ),
+            (
+                "<<EOF\nEOFX\n EOF\nEOF",
+                Some(HeredocMatch::new(19, 6, 10, false, false, true)),
+            ),
+            (
+                "<<-EOF\n\ta\n\tEOF",
+                Some(HeredocMatch::new(14, 7, 3, true, false, true)),
+            ),
+            (
+                "<<'a b'\n$x\na b",
+                Some(HeredocMatch::new(14, 8, 3, false, true, true)),
+            ),
+            (
+                "<<-\"EOF\"\nx\n\t\tEOF",
+                Some(HeredocMatch::new(16, 9, 2, true, true, true)),
+            ),
+            (
+                "<<-EOF\n  EOF",
+                Some(HeredocMatch::new(12, 7, 5, true, false, false)),
+            ),
+            (
+                "<<EOF\na\n",
+                Some(HeredocMatch::new(8, 6, 2, false, false, false)),
+            ),
+            (
+                "<<EOF",
+                Some(HeredocMatch::new(5, 5, 0, false, false, false)),
+            ),
+            (
+                "<<EOF x\nEOF",
+                Some(HeredocMatch::new(11, 8, 0, false, false, true)),
+            ),
+            (
+                "<<EOF > out\na\nEOF\n",
+                Some(HeredocMatch::new(17, 12, 2, false, false, true)),
+            ),
+            (
+                "<<-'EOF', x)\r\n\ta\n\tEOF",
+                Some(HeredocMatch::new(21, 14, 3, true, true, true)),
+            ),
+            (
+                "<<EOF | x",
+                Some(HeredocMatch::new(9, 9, 0, false, false, false)),
+            ),
+        ];
+
+        for (source, expected) in test_cases {
+            assert_eq!(heredoc.scan(source), *expected, "source: {:?}", source);
+        }
+
+        assert_eq!(heredoc.diagnose("<<EOF\nEOF"), None);
+        assert_eq!(
+            heredoc.diagnose("<<EOF\n").as_deref(),
+            Some("unterminated heredoc")
+        );
+    }
+
+    #[test]
+    fn fn_heredoc_deferred() {
+        let heredoc: Heredoc = Heredoc::default().with_indentation();
+
+        let test_cases: &[(&str, Option<usize>, bool)] = &[
+            ("<<EOF\nEOF", Some(9), false),
+            ("<<EOF x\nEOF", Some(5), true),
+            ("<<EOF\r\nEOF", Some(10), false),
+            ("<<-EOF, x)\n\tEOF", Some(6), true),
+            ("<<EOF|", Some(5), true),
+            ("<< EOF x", None, false),
+        ];
+
+        for (source, expected, deferred) in test_cases {
+            assert_eq!(heredoc.try_match(source), *expected, "source: {:?}", source);
+            let text: &str = &source[..expected.unwrap_or(0)];
+            assert_eq!(
+                heredoc.deferred_body(text).is_some(),
+                *deferred,
+                "source: {:?}",
+                source
+            );
+        }
+
+        let body: Arc<dyn Matcher> = heredoc.deferred_body("<<-EOF").unwrap();
+        assert_eq!(body.try_match("a\n\tEOF\nb"), Some(6));
+        assert_eq!(body.diagnose("a\n\tEOF"), None);
+        assert_eq!(body.try_match("a\nb"), Some(3));
+        assert_eq!(
+            body.diagnose("a\nb").as_deref(),
+            Some("unterminated heredoc")
+        );
+        assert_eq!(heredoc.diagnose("<<EOF"), None);
+    }
+
+    #[test]
+    fn fn_heredoc_options() {
+        let test_cases: &[(Heredoc, &str, Option<usize>)] = &[
+            (Heredoc::default(), "<<-EOF\nEOF", None),
+            (Heredoc::default(), "<<EOF\n EOF\n", Some(11)),
+            (Heredoc::default(), "<<'EOF'\nEOF", None),
+            (Heredoc::new("@\""), "@\"END\nx\nEND", Some(11)),
+        ];
+
+        for (heredoc, source, expected) in test_cases {
+            assert_eq!(heredoc.try_match(source), *expected, "source: {:?}", source);
+        }
+    }
+
+    #[test]
+    fn fn_heredoc_body() {
+        let heredoc: Heredoc = Heredoc::default().with_indentation();
+
+        let test_cases: &[(&str, &str)] = &[
+            ("<<EOF\n  a\nEOF", "  a\n"),
+            ("<<EOF\n\ta\nEOF", "\ta\n"),
+            ("<<-EOF\n\t\ta\n\t  b\n\n\tEOF", "a\n  b\n\n"),
+            ("<<-EOF\n  a\n\t b\nEOF", "  a\n b\n"),
+            ("<<-EOF\n\ta\n", "a\n"),
+        ];
+
+        for (source, expected) in test_cases {
+            let m: HeredocMatch = heredoc.scan(source).unwrap();
+            assert_eq!(m.body(source), *expected, "source: {:?}", source);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::lexer::Span;
+
+/// A heredoc matched by a [Heredoc] matcher.
+///
+/// [Heredoc]: crate::lexer::matchers::Heredoc
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct HeredocMatch {
+    len: usize,
+    body_offset: usize,
+    body_len: usize,
+    indented: bool,
+    quoted: bool,
+    terminated: bool,
+}
+
+impl HeredocMatch {
+    //! Construction
+
+    /// Creates a new heredoc match.
+    pub fn new(
+        len: usize,
+        body_offset: usize,
+        body_len: usize,
+        indented: bool,
+        quoted: bool,
+        terminated: bool,
+    ) -> Self {
+        Self {
+            len,
+            body_offset,
+            body_len,
+            indented,
+            quoted,
+            terminated,
+        }
+    }
+}
+
+impl HeredocMatch {
+    //! Properties
+
+    /// Gets the length of the heredoc, from the opener through the closing tag.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Gets the offset of the body (the lines between the opening and closing lines) from the
+    /// start of the heredoc.
+    pub fn body_offset(&self) -> usize {
+        self.body_offset
+    }
+
+    /// Gets the length of the body, including its final line ending.
+    pub fn body_len(&self) -> usize {
+        self.body_len
+    }
+
+    /// Gets the body span inside the heredoc token's `span`.
+    pub fn body_span(&self, span: Span) -> Span {
+        Span::new(
+            span.offset() + self.body_offset as u32,
+            self.body_len as u32,
+        )
+    }
+
+    /// Checks if the heredoc uses the indented form. (`<<-`)
+    pub fn is_indented(&self) -> bool {
+        self.indented
+    }
+
+    /// Checks if the tag is quoted. (e.g. `<<'EOF'`, conventionally without interpolation)
+    pub fn is_quoted(&self) -> bool {
+        self.quoted
+    }
+
+    /// Checks if the heredoc has its closing tag.
+    ///
+    /// An unterminated heredoc extends to the end of the source.
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl HeredocMatch {
+    //! Body
+
+    /// Gets the body from the heredoc `text`.
+    ///
+    /// For indented heredocs all leading tabs are stripped from each body line, as for `<<-` in
+    /// POSIX shells. Leading spaces are kept.
+    pub fn body(&self, text: &str) -> String {
+        let body: &str = &text[self.body_offset..self.body_offset + self.body_len];
+        if !self.indented {
+            return body.to_string();
+        }
+
+        body.split_inclusive('\n')
+            .map(|line| line.trim_start_matches('\t'))
+            .collect()
+    }
+}
@@ -30,8 +30,50 @@ macro_rules! keyword {
     };
 }
 
+/// Matches a keyword at a Unicode word boundary: only if not followed by a Unicode identifier
+/// character (XID_Continue). Pairs with the [unicode_ident] matcher.
+///
+/// # Example
+/// ```
+/// use lex::keyword_unicode;
+/// use lex::lexer::Matcher;
+///
+/// let matcher = keyword_unicode!("if");
+/// assert_eq!(matcher.try_match("if x"), Some(2));
+/// assert_eq!(matcher.try_match("ifé"), None);
+/// ```
+///
+/// [unicode_ident]: crate::lexer::matchers::unicode_ident
+#[macro_export]
+macro_rules! keyword_unicode {
+    ($s:literal) => {
+        $crate::lexer::matchers::Literal::keyword($s).with_unicode_boundary()
+    };
+}
+
+/// Matches a keyword ignoring ASCII case, only if not followed by an identifier character
+/// (`[a-zA-Z0-9_]`).
+///
+/// # Example
+/// ```
+/// use lex::keyword_ci;
+/// use lex::lexer::Matcher;
+///
+/// let matcher = keyword_ci!("select");
+/// assert_eq!(matcher.try_match("SELECT *"), Some(6));
+/// assert_eq!(matcher.try_match("SeLeCt"), Some(6));
+/// assert_eq!(matcher.try_match("selected"), None);
+/// ```
+#[macro_export]
+macro_rules! keyword_ci {
+    ($s:literal) => {
+        $crate::lexer::matchers::Literal::keyword($s).with_ignore_case()
+    };
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::lexer::Matcher;
 
     #[test]
     fn fn_keyword() {
@@ -52,4 +94,42 @@ mod tests {
             assert_eq!(matcher(source), *expected, "source: {:?}", source);
         }
     }
+
+    #[test]
+    fn fn_keyword_ci() {
+        let matcher = keyword_ci!("select");
+
+        let test_cases: &[(&str, Option<usize>)] = &[
+            ("", None),
+            ("sel", None),
+            ("selected", None),
+            ("SELECT_1", None),
+            ("select", Some(6)),
+            ("SELECT *", Some(6)),
+            ("SeLeCt\n", Some(6)),
+        ];
+
+        for (source, expected) in test_cases {
+            assert_eq!(matcher.try_match(source), *expected, "source: {:?}", source);
+        }
+    }
+
+    #[test]
+    fn fn_keyword_unicode() {
+        let matcher = keyword_unicode!("let");
+
+        let test_cases: &[(&str, Option<usize>)] = &[
+            ("", None),
+            ("letter", None),
+            ("leté", None),
+            ("let\u{301}", None),
+            ("let", Some(3)),
+            ("let x", Some(3)),
+            ("let€", Some(3)),
+        ];
+
+        for (source, expected) in test_cases {
+            assert_eq!(matcher.try_match(source), *expected, "source: {:?}", source);
+        }
+    }
 }
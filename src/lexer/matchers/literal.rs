@@ -1,3 +1,149 @@
+use crate::lexer::matchers::xid::is_xid_continue;
+use crate::lexer::{ByteSet, Matcher};
+
+/// A literal matcher. Matches an exact string, optionally only at a word boundary or ignoring
+/// ASCII case.
+///
+/// Runs of consecutive `Literal` rules are merged into a single trie by the [Lexer]. The `literal!`
+/// and `keyword!` rules of a `lexer!` grammar are `Literal` matchers; outside `lexer!` the macros
+/// expand to plain fn matchers.
+///
+/// # Example
+/// ```
+/// use lex::lexer::Lexer;
+/// use lex::lexer::matchers::Literal;
+///
+/// let lexer: Lexer<u8> = Lexer::default()
+///     .with_rule(0, Literal::new("=="))
+///     .with_rule(1, Literal::new("="))
+///     .with_rule(2, Literal::keyword("if"));
+/// ```
+///
+/// [Lexer]: crate::lexer::Lexer
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Literal {
+    text: String,
+    keyword: bool,
+    unicode_boundary: bool,
+    ignore_case: bool,
+}
+
+impl Literal {
+    //! Construction
+
+    /// Creates a new literal matcher for the exact `text`.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            keyword: false,
+            unicode_boundary: false,
+            ignore_case: false,
+        }
+    }
+
+    /// Creates a new keyword matcher for the exact `text`.
+    ///
+    /// Only matches if not followed by an identifier character (`[a-zA-Z0-9_]`).
+    pub fn keyword(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            keyword: true,
+            unicode_boundary: false,
+            ignore_case: false,
+        }
+    }
+
+    /// Makes the keyword only match if not followed by a Unicode identifier character
+    /// (XID_Continue). Has no effect unless the literal is a keyword. (builder pattern)
+    #[must_use]
+    pub fn with_unicode_boundary(mut self) -> Self {
+        self.unicode_boundary = true;
+        self
+    }
+
+    /// Makes the literal match ignoring ASCII case. (builder pattern)
+    #[must_use]
+    pub fn with_ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self
+    }
+}
+
+impl Literal {
+    //! Properties
+
+    /// Gets the literal text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Checks if the literal only matches at a word boundary.
+    pub fn is_keyword(&self) -> bool {
+        self.keyword
+    }
+
+    /// Checks if the keyword boundary is a Unicode word boundary.
+    pub fn has_unicode_boundary(&self) -> bool {
+        self.unicode_boundary
+    }
+
+    /// Checks if the literal matches ignoring ASCII case.
+    pub fn ignores_case(&self) -> bool {
+        self.ignore_case
+    }
+}
+
+impl Literal {
+    //! Matching
+
+    /// Checks if the `source` starts with the literal text.
+    fn matches_text(&self, source: &str) -> bool {
+        if self.ignore_case {
+            let prefix: Option<&[u8]> = source.as_bytes().get(..self.text.len());
+            prefix.is_some_and(|prefix| prefix.eq_ignore_ascii_case(self.text.as_bytes()))
+        } else {
+            source.starts_with(self.text.as_str())
+        }
+    }
+
+    /// Checks if the literal ends at an acceptable position: `source[len..]` is the rest of the
+    /// source after the literal text.
+    fn accepts_end(&self, source: &str, len: usize) -> bool {
+        !self.keyword
+            || if self.unicode_boundary {
+                let next: Option<char> = source[len..].chars().next();
+                next.is_none_or(|next| !is_xid_continue(next))
+            } else {
+                let next: Option<&u8> = source.as_bytes().get(len);
+                next.is_none_or(|next| !next.is_ascii_alphanumeric() && *next != b'_')
+            }
+    }
+}
+
+impl Matcher for Literal {
+    fn try_match(&self, source: &str) -> Option<usize> {
+        let len: usize = self.text.len();
+        (self.matches_text(source) && self.accepts_end(source, len)).then_some(len)
+    }
+
+    fn literal(&self) -> Option<&Literal> {
+        Some(self)
+    }
+
+    fn first_bytes(&self) -> Option<ByteSet> {
+        let first: Option<u8> = self.text.bytes().next();
+        Some(first.map_or(ByteSet::EMPTY, |first| {
+            if self.ignore_case {
+                ByteSet::EMPTY
+                    .with_byte(first.to_ascii_lowercase())
+                    .with_byte(first.to_ascii_uppercase())
+            } else {
+                ByteSet::EMPTY.with_byte(first)
+            }
+        }))
+    }
+}
+
 /// Matches an exact string literal.
 ///
 /// # Example
@@ -15,8 +161,29 @@ macro_rules! literal {
     };
 }
 
+/// Matches an exact string literal ignoring ASCII case.
+///
+/// # Example
+/// ```
+/// use lex::lexer::Matcher;
+/// use lex::literal_ci;
+///
+/// let matcher = literal_ci!("<>");
+/// assert_eq!(matcher.try_match("<> 1"), Some(2));
+///
+/// let matcher = literal_ci!("is not");
+/// assert_eq!(matcher.try_match("IS Not NULL"), Some(6));
+/// ```
+#[macro_export]
+macro_rules! literal_ci {
+    ($s:literal) => {
+        $crate::lexer::matchers::Literal::new($s).with_ignore_case()
+    };
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::lexer::Matcher;
 
     #[test]
     fn fn_literal() {
@@ -35,4 +202,23 @@ mod tests {
             assert_eq!(matcher(source), *expected, "source: {:?}", source);
         }
     }
+
+    #[test]
+    fn fn_literal_ci() {
+        let matcher = literal_ci!("Order By");
+
+        let test_cases: &[(&str, Option<usize>)] = &[
+            ("", None),
+            ("order", None),
+            ("order  by", None),
+            ("order by", Some(8)),
+            ("ORDER BY x", Some(8)),
+            ("oRdEr bY", Some(8)),
+            ("orderéby", None),
+        ];
+
+        for (source, expected) in test_cases {
+            assert_eq!(matcher.try_match(source), *expected, "source: {:?}", source);
+        }
+    }
 }
@@ -1,10 +1,36 @@
+pub use block_comment::*;
 pub use digits::*;
+pub use heredoc::*;
+pub use heredoc_match::*;
 pub use ident::*;
+pub use literal::*;
+pub use number::*;
+pub use number_form::*;
+pub use number_match::*;
+pub use pattern::*;
+pub use pattern_error::*;
+pub use radix::*;
+pub use string_literal::*;
+pub use string_match::*;
+pub use unicode_ident::*;
 pub use whitespace::*;
 
+mod block_comment;
 mod digits;
+mod heredoc;
+mod heredoc_match;
 mod ident;
 mod keyword;
 mod line_comment;
 mod literal;
+mod number;
+mod number_form;
+mod number_match;
+mod pattern;
+mod pattern_error;
+mod radix;
+mod string_literal;
+mod string_match;
+mod unicode_ident;
 mod whitespace;
+mod xid;
@@ -0,0 +1,322 @@
+use crate::lexer::matchers::{NumberForm, NumberMatch, Radix};
+use crate::lexer::{ByteSet, Matcher};
+
+/// A configurable number literal matcher.
+///
+/// By default only matches decimal integers (`[0-9]+`). Prefixed radixes, floats, `_` separators
+/// and suffixes are enabled with the builder methods. A literal directly followed by an
+/// identifier character or digit is malformed (e.g. `0x`, `0b102` or `1e`) and does not match.
+///
+/// # Example
+/// ```
+/// use lex::lexer::Matcher;
+/// use lex::lexer::matchers::{Number, NumberForm, Radix};
+///
+/// let number: Number = Number::default()
+///     .with_hexadecimal()
+///     .with_floats()
+///     .with_separators()
+///     .with_suffix("u32");
+/// assert_eq!(number.try_match("0x1F_FF;"), Some(7));
+/// assert_eq!(number.try_match("6.02e23"), Some(7));
+/// assert_eq!(number.try_match("0x"), None);
+///
+/// let m = number.scan("1_000u32").unwrap();
+/// assert_eq!((m.radix(), m.form(), m.suffix_len()), (Radix::Decimal, NumberForm::Integer, 3));
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Number {
+    binary: bool,
+    octal: bool,
+    hexadecimal: bool,
+    floats: bool,
+    trailing_dot: bool,
+    separators: bool,
+    suffixes: Vec<String>,
+}
+
+impl Number {
+    //! Builders
+
+    /// Enables `0b` prefixed binary integers. (builder pattern)
+    #[must_use]
+    pub fn with_binary(mut self) -> Self {
+        self.binary = true;
+        self
+    }
+
+    /// Enables `0o` prefixed octal integers. (builder pattern)
+    #[must_use]
+    pub fn with_octal(mut self) -> Self {
+        self.octal = true;
+        self
+    }
+
+    /// Enables `0x` prefixed hexadecimal integers. (builder pattern)
+    #[must_use]
+    pub fn with_hexadecimal(mut self) -> Self {
+        self.hexadecimal = true;
+        self
+    }
+
+    /// Enables decimal floats with a fraction (`3.14`) and/or an exponent (`6.02e23`).
+    /// (builder pattern)
+    ///
+    /// The fraction needs at least one digit: `1.` matches only the integer `1`.
+    #[must_use]
+    pub fn with_floats(mut self) -> Self {
+        self.floats = true;
+        self
+    }
+
+    /// Enables floats with an empty fraction, like `1.`, unless followed by another `.` or an
+    /// identifier start. Implies floats. (builder pattern)
+    #[must_use]
+    pub fn with_trailing_dot(mut self) -> Self {
+        self.floats = true;
+        self.trailing_dot = true;
+        self
+    }
+
+    /// Enables `_` separators between digits. (builder pattern)
+    #[must_use]
+    pub fn with_separators(mut self) -> Self {
+        self.separators = true;
+        self
+    }
+
+    /// Adds an accepted `suffix`, like `u32` or `f64`. (builder pattern)
+    #[must_use]
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffixes.push(suffix.into());
+        self
+    }
+}
+
+impl Number {
+    //! Matching
+
+    /// Scans the number literal at the start of the `source`.
+    pub fn scan(&self, source: &str) -> Option<NumberMatch> {
+        let bytes: &[u8] = source.as_bytes();
+        let radix: Radix = match bytes.get(..2) {
+            Some(b"0b" | b"0B") if self.binary => Radix::Binary,
+            Some(b"0o" | b"0O") if self.octal => Radix::Octal,
+            Some(b"0x" | b"0X") if self.hexadecimal => Radix::Hexadecimal,
+            _ => Radix::Decimal,
+        };
+
+        let mut len: usize = if radix == Radix::Decimal { 0 } else { 2 };
+        len = self.scan_digits(bytes, len, radix)?;
+
+        let mut form: NumberForm = NumberForm::Integer;
+        if self.floats && radix == Radix::Decimal {
+            if bytes.get(len) == Some(&b'.') {
+                if let Some(end) = self.scan_digits(bytes, len + 1, radix) {
+                    len = end;
+                    form = NumberForm::Float;
+                } else if self.trailing_dot
+                    && bytes
+                        .get(len + 1)
+                        .is_none_or(|next| *next != b'.' && !is_ident_byte(*next))
+                {
+                    len += 1;
+                    form = NumberForm::Float;
+                }
+            }
+            if matches!(bytes.get(len), Some(b'e' | b'E')) {
+                let sign: usize = usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+                if let Some(end) = self.scan_digits(bytes, len + 1 + sign, radix) {
+                    len = end;
+                    form = NumberForm::Float;
+                }
+            }
+        }
+
+        let separators: usize = if self.separators {
+            bytes[len..].iter().take_while(|b| **b == b'_').count()
+        } else {
+            0
+        };
+        let suffix_len: usize = self
+            .suffixes
+            .iter()
+            .filter(|suffix| source[len + separators..].starts_with(suffix.as_str()))
+            .map(String::len)
+            .max()
+            .map_or(0, |len| separators + len);
+        len += suffix_len;
+
+        if bytes.get(len).is_some_and(|next| is_ident_byte(*next)) {
+            return None;
+        }
+        Some(NumberMatch::new(len, radix, form, suffix_len))
+    }
+
+    /// Scans the digits of the `radix` starting at `start`. Returns the end of the digits.
+    ///
+    /// Returns `None` if there are no digits.
+    fn scan_digits(&self, bytes: &[u8], start: usize, radix: Radix) -> Option<usize> {
+        let is_digit = |b: u8| (b as char).is_digit(radix.base());
+        let mut end: usize = start;
+        let mut pos: usize = start;
+        while let Some(b) = bytes.get(pos) {
+            if is_digit(*b) {
+                pos += 1;
+                end = pos;
+            } else if self.separators && *b == b'_' && end > start {
+                pos += 1;
+            } else {
+                break;
+            }
+        }
+        (end > start).then_some(end)
+    }
+}
+
+/// Checks if the byte can continue an identifier or number.
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+impl Matcher for Number {
+    fn try_match(&self, source: &str) -> Option<usize> {
+        self.scan(source).map(|m| m.len())
+    }
+
+    fn first_bytes(&self) -> Option<ByteSet> {
+        Some(ByteSet::ASCII_DIGIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_number() {
+        let number: Number = Number::default()
+            .with_binary()
+            .with_octal()
+            .with_hexadecimal()
+            .with_floats()
+            .with_separators()
+            .with_suffix("u32")
+            .with_suffix("u8")
+            .with_suffix("f64");
+
+        let test_cases: &[(&str, Option<NumberMatch>)] = &[
+            ("", None),
+            ("x", None),
+            ("_1", None),
+            (
+                "0",
+                Some(NumberMatch::new(1, Radix::Decimal, NumberForm::Integer, 0)),
+            ),
+            (
+                "123;",
+                Some(NumberMatch::new(3, Radix::Decimal, NumberForm::Integer, 0)),
+            ),
+            (
+                "1_000_000",
+                Some(NumberMatch::new(9, Radix::Decimal, NumberForm::Integer, 0)),
+            ),
+            ("1_000_", None),
+            (
+                "0x1F",
+                Some(NumberMatch::new(
+                    4,
+                    Radix::Hexadecimal,
+                    NumberForm::Integer,
+                    0,
+                )),
+            ),
+            ("0X_ff", None),
+            ("0x", None),
+            ("0xG", None),
+            (
+                "0b1010",
+                Some(NumberMatch::new(6, Radix::Binary, NumberForm::Integer, 0)),
+            ),
+            ("0b102", None),
+            (
+                "0o17",
+                Some(NumberMatch::new(4, Radix::Octal, NumberForm::Integer, 0)),
+            ),
+            ("0o8", None),
+            (
+                "3.14",
+                Some(NumberMatch::new(4, Radix::Decimal, NumberForm::Float, 0)),
+            ),
+            (
+                "6.02e23",
+                Some(NumberMatch::new(7, Radix::Decimal, NumberForm::Float, 0)),
+            ),
+            (
+                "1e-9",
+                Some(NumberMatch::new(4, Radix::Decimal, NumberForm::Float, 0)),
+            ),
+            (
+                "1E+9",
+                Some(NumberMatch::new(4, Radix::Decimal, NumberForm::Float, 0)),
+            ),
+            ("1e", None),
+            (
+                "1.",
+                Some(NumberMatch::new(1, Radix::Decimal, NumberForm::Integer, 0)),
+            ),
+            (
+                "1..2",
+                Some(NumberMatch::new(1, Radix::Decimal, NumberForm::Integer, 0)),
+            ),
+            (
+                "1.max",
+                Some(NumberMatch::new(1, Radix::Decimal, NumberForm::Integer, 0)),
+            ),
+            (
+                "1u32",
+                Some(NumberMatch::new(4, Radix::Decimal, NumberForm::Integer, 3)),
+            ),
+            (
+                "1u8 ",
+                Some(NumberMatch::new(3, Radix::Decimal, NumberForm::Integer, 2)),
+            ),
+            ("1u16", None),
+            (
+                "2.5f64",
+                Some(NumberMatch::new(6, Radix::Decimal, NumberForm::Float, 3)),
+            ),
+            (
+                "0xFFu8",
+                Some(NumberMatch::new(
+                    6,
+                    Radix::Hexadecimal,
+                    NumberForm::Integer,
+                    2,
+                )),
+            ),
+        ];
+
+        for (source, expected) in test_cases {
+            assert_eq!(number.scan(source), *expected, "source: {:?}", source);
+        }
+    }
+
+    #[test]
+    fn fn_number_options() {
+        let test_cases: &[(Number, &str, Option<usize>)] = &[
+            (Number::default(), "0x1F", None),
+            (Number::default(), "1_000", None),
+            (Number::default(), "3.14", Some(1)),
+            (Number::default().with_trailing_dot(), "1.", Some(2)),
+            (Number::default().with_trailing_dot(), "1. ", Some(2)),
+            (Number::default().with_trailing_dot(), "1..2", Some(1)),
+            (Number::default().with_trailing_dot(), "1.max", Some(1)),
+            (Number::default().with_floats(), "0x1.5", None),
+        ];
+
+        for (number, source, expected) in test_cases {
+            assert_eq!(number.try_match(source), *expected, "source: {:?}", source);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+/// The form of a number literal.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum NumberForm {
+    /// An integer: digits only.
+    Integer,
+
+    /// A float: decimal digits with a fraction and/or an exponent.
+    Float,
+}
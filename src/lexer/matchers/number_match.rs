@@ -0,0 +1,52 @@
+use crate::lexer::matchers::{NumberForm, Radix};
+
+/// A number literal matched by a [Number] matcher.
+///
+/// [Number]: crate::lexer::matchers::Number
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct NumberMatch {
+    len: usize,
+    radix: Radix,
+    form: NumberForm,
+    suffix_len: usize,
+}
+
+impl NumberMatch {
+    //! Construction
+
+    /// Creates a new number match.
+    pub fn new(len: usize, radix: Radix, form: NumberForm, suffix_len: usize) -> Self {
+        Self {
+            len,
+            radix,
+            form,
+            suffix_len,
+        }
+    }
+}
+
+impl NumberMatch {
+    //! Properties
+
+    /// Gets the length of the literal, including the prefix and suffix.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Gets the radix.
+    pub fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    /// Gets the form.
+    pub fn form(&self) -> NumberForm {
+        self.form
+    }
+
+    /// Gets the length of the suffix, including any `_` separators before it. (0 if there is no
+    /// suffix)
+    pub fn suffix_len(&self) -> usize {
+        self.suffix_len
+    }
+}
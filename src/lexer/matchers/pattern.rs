@@ -0,0 +1,767 @@
+use crate::lexer::matchers::PatternError;
+use crate::lexer::{ByteSet, Matcher};
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+
+/// The maximum number of compiled instructions in a pattern.
+const MAX_INSTRUCTIONS: usize = 10_000;
+
+/// The maximum nesting depth of groups and quantifiers in a pattern.
+const MAX_DEPTH: usize = 64;
+
+thread_local! {
+    /// The scratch buffers reused by [Pattern::try_match] on this thread.
+    static SCRATCH: RefCell<Scratch> = RefCell::default();
+}
+
+/// A pattern matcher. Matches the longest prefix of the source described by a small regular
+/// expression language.
+///
+/// Supported syntax:
+/// - `a`, `\.` — a literal char (escape any of `\.[]()|*+?{}^$-`)
+/// - `.` — any char except `\n`
+/// - `[a-z_]`, `[^0-9]` — a char class with ranges, optionally negated
+/// - `\d`, `\w`, `\s` — ASCII digits, word chars, and whitespace (`\D`, `\W`, `\S` negated)
+/// - `\n`, `\r`, `\t`, `\0` — control chars
+/// - `*`, `+`, `?`, `{n}`, `{n,}`, `{n,m}` — repetition
+/// - `a|b` — alternation
+/// - `(a)` — grouping
+///
+/// Patterns expanding to more than 10,000 instructions or nesting groups and quantifiers more
+/// than 64 deep are rejected with a [PatternError].
+///
+/// # Example
+/// ```
+/// use lex::lexer::Matcher;
+/// use lex::lexer::matchers::Pattern;
+///
+/// let matcher: Pattern = Pattern::new("#[0-9a-fA-F]{6}").unwrap();
+/// assert_eq!(matcher.try_match("#1F2e3D;"), Some(7));
+/// assert_eq!(matcher.try_match("#1F2"), None);
+/// ```
+#[derive(Clone)]
+pub struct Pattern {
+    source: String,
+    program: Vec<Inst>,
+    classes: Vec<Class>,
+    first_bytes: ByteSet,
+}
+
+/// The scratch buffers of a match: the current and next threads, the threads seen at the current
+/// position, and the stack of pending threads.
+#[derive(Default)]
+struct Scratch {
+    threads: Vec<usize>,
+    next: Vec<usize>,
+    seen: Vec<bool>,
+    stack: Vec<usize>,
+}
+
+/// A compiled instruction.
+#[derive(Copy, Clone, Debug)]
+enum Inst {
+    /// Consumes a char in the class at the index.
+    Class(usize),
+
+    /// Continues at both targets.
+    Split(usize, usize),
+
+    /// Continues at the target.
+    Jump(usize),
+
+    /// Accepts the input consumed so far.
+    Match,
+}
+
+/// A set of chars as sorted, non-overlapping inclusive ranges of code points.
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct Class {
+    ranges: Vec<(u32, u32)>,
+}
+
+/// A parsed pattern node.
+#[derive(Clone, Debug)]
+enum Node {
+    Empty,
+    Class(Class),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat(Box<Node>, u32, Option<u32>),
+}
+
+impl Pattern {
+    //! Construction
+
+    /// Compiles the `pattern`.
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        let mut parser: PatternParser = PatternParser {
+            pattern,
+            pos: 0,
+            depth: 0,
+        };
+        let node: Node = parser.parse_alternate()?;
+        if parser.pos < pattern.len() {
+            return Err(PatternError::new(parser.pos, "unmatched ')'"));
+        }
+        if node.size() > MAX_INSTRUCTIONS {
+            return Err(PatternError::new(0, "pattern too large"));
+        }
+
+        let mut compiled: Self = Self {
+            source: pattern.to_string(),
+            program: Vec::default(),
+            classes: Vec::default(),
+            first_bytes: ByteSet::EMPTY,
+        };
+        compiled.compile(&node)?;
+        compiled.program.push(Inst::Match);
+        compiled.first_bytes = compiled.compute_first_bytes();
+        Ok(compiled)
+    }
+}
+
+impl Pattern {
+    //! Properties
+
+    /// Gets the pattern source.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Pattern {
+    //! Compilation
+
+    /// Compiles the `node` onto the end of the program.
+    fn compile(&mut self, node: &Node) -> Result<(), PatternError> {
+        if self.program.len() > MAX_INSTRUCTIONS {
+            return Err(PatternError::new(0, "pattern too large"));
+        }
+        match node {
+            Node::Empty => {}
+            Node::Class(class) => {
+                let index: usize = match self.classes.iter().position(|c| c == class) {
+                    Some(index) => index,
+                    None => {
+                        self.classes.push(class.clone());
+                        self.classes.len() - 1
+                    }
+                };
+                self.program.push(Inst::Class(index));
+            }
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.compile(node)?;
+                }
+            }
+            Node::Alternate(nodes) => {
+                let mut jumps: Vec<usize> = Vec::default();
+                for (i, node) in nodes.iter().enumerate() {
+                    if i + 1 < nodes.len() {
+                        let split: usize = self.program.len();
+                        self.program.push(Inst::Split(split + 1, 0));
+                        self.compile(node)?;
+                        jumps.push(self.program.len());
+                        self.program.push(Inst::Jump(0));
+                        self.program[split] = Inst::Split(split + 1, self.program.len());
+                    } else {
+                        self.compile(node)?;
+                    }
+                }
+                for jump in jumps {
+                    self.program[jump] = Inst::Jump(self.program.len());
+                }
+            }
+            Node::Repeat(node, min, max) => {
+                for _ in 0..*min {
+                    self.compile(node)?;
+                }
+                match max {
+                    None => {
+                        let split: usize = self.program.len();
+                        self.program.push(Inst::Split(split + 1, 0));
+                        self.compile(node)?;
+                        self.program.push(Inst::Jump(split));
+                        self.program[split] = Inst::Split(split + 1, self.program.len());
+                    }
+                    Some(max) => {
+                        let mut splits: Vec<usize> = Vec::default();
+                        for _ in *min..*max {
+                            splits.push(self.program.len());
+                            self.program.push(Inst::Split(0, 0));
+                            self.compile(node)?;
+                        }
+                        let end: usize = self.program.len();
+                        for split in splits {
+                            self.program[split] = Inst::Split(split + 1, end);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Pattern {
+    //! Matching
+
+    /// Adds the thread at `pc` and the threads reachable from it without consuming input.
+    fn add_thread(
+        &self,
+        threads: &mut Vec<usize>,
+        seen: &mut [bool],
+        stack: &mut Vec<usize>,
+        pc: usize,
+    ) {
+        stack.push(pc);
+        while let Some(pc) = stack.pop() {
+            if seen[pc] {
+                continue;
+            }
+            seen[pc] = true;
+            match self.program[pc] {
+                Inst::Split(a, b) => {
+                    stack.push(b);
+                    stack.push(a);
+                }
+                Inst::Jump(target) => stack.push(target),
+                Inst::Class(_) | Inst::Match => threads.push(pc),
+            }
+        }
+    }
+}
+
+impl Pattern {
+    //! Matching
+
+    /// Gets the set of bytes every match can start with.
+    fn compute_first_bytes(&self) -> ByteSet {
+        let mut threads: Vec<usize> = Vec::default();
+        let mut seen: Vec<bool> = vec![false; self.program.len()];
+        self.add_thread(&mut threads, &mut seen, &mut Vec::default(), 0);
+
+        let mut set: ByteSet = ByteSet::EMPTY;
+        for pc in threads {
+            if let Inst::Class(class) = self.program[pc] {
+                set = set.with_set(self.classes[class].first_bytes());
+            }
+        }
+        set
+    }
+
+    /// Matches the `source` with the `scratch` buffers.
+    fn run(&self, source: &str, scratch: &mut Scratch) -> Option<usize> {
+        let Scratch {
+            threads,
+            next,
+            seen,
+            stack,
+        } = scratch;
+        threads.clear();
+        seen.clear();
+        seen.resize(self.program.len(), false);
+        let mut best: Option<usize> = None;
+
+        self.add_thread(threads, seen, stack, 0);
+        for (i, c) in source.char_indices() {
+            seen.fill(false);
+            next.clear();
+            for pc in threads.iter() {
+                if let Inst::Class(class) = self.program[*pc]
+                    && self.classes[class].contains(c)
+                {
+                    self.add_thread(next, seen, stack, pc + 1);
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            if next
+                .iter()
+                .any(|pc| matches!(self.program[*pc], Inst::Match))
+            {
+                best = Some(i + c.len_utf8());
+            }
+            std::mem::swap(threads, next);
+        }
+        best
+    }
+}
+
+impl Matcher for Pattern {
+    fn try_match(&self, source: &str) -> Option<usize> {
+        let first: u8 = *source.as_bytes().first()?;
+        if !self.first_bytes.contains(first) {
+            return None;
+        }
+        SCRATCH.with_borrow_mut(|scratch| self.run(source, scratch))
+    }
+
+    fn first_bytes(&self) -> Option<ByteSet> {
+        Some(self.first_bytes)
+    }
+}
+
+impl Matcher for &'static Pattern {
+    fn try_match(&self, source: &str) -> Option<usize> {
+        (**self).try_match(source)
+    }
+
+    fn first_bytes(&self) -> Option<ByteSet> {
+        (**self).first_bytes()
+    }
+}
+
+impl Debug for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Pattern").field(&self.source).finish()
+    }
+}
+
+impl Class {
+    //! Construction
+
+    /// Creates a class from the unsorted, possibly overlapping `ranges`.
+    fn new(mut ranges: Vec<(u32, u32)>) -> Self {
+        ranges.sort_unstable();
+        let mut merged: Vec<(u32, u32)> = Vec::with_capacity(ranges.len());
+        for (lo, hi) in ranges {
+            match merged.last_mut() {
+                Some((_, last_hi)) if lo <= last_hi.saturating_add(1) => {
+                    *last_hi = (*last_hi).max(hi)
+                }
+                _ => merged.push((lo, hi)),
+            }
+        }
+        Self { ranges: merged }
+    }
+
+    /// Creates a class with the single char `c`.
+    fn char(c: char) -> Self {
+        Self::new(vec![(c as u32, c as u32)])
+    }
+
+    /// Gets the complement of the class.
+    fn negate(&self) -> Self {
+        let mut ranges: Vec<(u32, u32)> = Vec::default();
+        let mut next: u32 = 0;
+        for (lo, hi) in &self.ranges {
+            if *lo > next {
+                ranges.push((next, lo - 1));
+            }
+            next = hi + 1;
+        }
+        if next <= char::MAX as u32 {
+            ranges.push((next, char::MAX as u32));
+        }
+        Self { ranges }
+    }
+}
+
+impl Class {
+    //! Properties
+
+    /// Gets the code point if the class contains exactly one char.
+    fn single(&self) -> Option<u32> {
+        match self.ranges[..] {
+            [(lo, hi)] if lo == hi => Some(lo),
+            _ => None,
+        }
+    }
+
+    /// Checks if the class contains the char `c`.
+    fn contains(&self, c: char) -> bool {
+        let c: u32 = c as u32;
+        self.ranges
+            .binary_search_by(|(lo, hi)| {
+                if *hi < c {
+                    std::cmp::Ordering::Less
+                } else if *lo > c {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// Gets the set of first UTF-8 bytes of the chars in the class.
+    fn first_bytes(&self) -> ByteSet {
+        let mut set: ByteSet = ByteSet::EMPTY;
+        for (lo, hi) in &self.ranges {
+            if *lo < 0x80 {
+                set = set.with_range(*lo as u8, (*hi).min(0x7F) as u8);
+            }
+            if *hi >= 0x80 {
+                set = set.with_range(0xC2, 0xF4);
+            }
+        }
+        set
+    }
+}
+
+impl Node {
+    //! Properties
+
+    /// Gets an upper bound of the number of compiled instructions. Empty nodes count as one so
+    /// repeating them is bounded too.
+    fn size(&self) -> usize {
+        match self {
+            Self::Empty | Self::Class(_) => 1,
+            Self::Concat(nodes) => nodes
+                .iter()
+                .fold(0, |size, node| size.saturating_add(node.size())),
+            Self::Alternate(nodes) => nodes.iter().fold(0, |size, node| {
+                size.saturating_add(node.size()).saturating_add(2)
+            }),
+            Self::Repeat(node, min, max) => {
+                let size: usize = node.size();
+                let optional: usize = match max {
+                    Some(max) => ((max - min) as usize).saturating_mul(size.saturating_add(1)),
+                    None => size.saturating_add(2),
+                };
+                (*min as usize)
+                    .saturating_mul(size)
+                    .saturating_add(optional)
+            }
+        }
+    }
+}
+
+/// A recursive descent pattern parser.
+struct PatternParser<'a> {
+    pattern: &'a str,
+    pos: usize,
+
+    /// The nesting depth of the group being parsed.
+    depth: usize,
+}
+
+impl PatternParser<'_> {
+    //! Parsing
+
+    /// Peeks at the next char.
+    fn peek(&self) -> Option<char> {
+        self.pattern[self.pos..].chars().next()
+    }
+
+    /// Consumes the next char.
+    fn next(&mut self) -> Option<char> {
+        let c: char = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// Parses an alternation: `concat ('|' concat)*`.
+    fn parse_alternate(&mut self) -> Result<Node, PatternError> {
+        let mut nodes: Vec<Node> = vec![self.parse_concat()?];
+        while self.peek() == Some('|') {
+            self.next();
+            nodes.push(self.parse_concat()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.pop().unwrap()
+        } else {
+            Node::Alternate(nodes)
+        })
+    }
+
+    /// Parses a concatenation of repeated atoms.
+    fn parse_concat(&mut self) -> Result<Node, PatternError> {
+        let mut nodes: Vec<Node> = Vec::default();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom: Node = self.parse_atom()?;
+            nodes.push(self.parse_repeat(atom)?);
+        }
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().unwrap(),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    /// Parses the quantifiers following the `atom`.
+    fn parse_repeat(&mut self, mut atom: Node) -> Result<Node, PatternError> {
+        let mut count: usize = 0;
+        loop {
+            if count + self.depth > MAX_DEPTH {
+                return Err(PatternError::new(self.pos, "pattern nested too deeply"));
+            }
+            count += 1;
+            let (min, max): (u32, Option<u32>) = match self.peek() {
+                Some('*') => (0, None),
+                Some('+') => (1, None),
+                Some('?') => (0, Some(1)),
+                Some('{') => {
+                    let start: usize = self.pos;
+                    self.next();
+                    let (min, max): (u32, Option<u32>) = self.parse_bounds(start)?;
+                    atom = Node::Repeat(Box::new(atom), min, max);
+                    continue;
+                }
+                _ => return Ok(atom),
+            };
+            self.next();
+            atom = Node::Repeat(Box::new(atom), min, max);
+        }
+    }
+
+    /// Parses the bounds of a `{n}`, `{n,}` or `{n,m}` quantifier after the `{` at `start`.
+    fn parse_bounds(&mut self, start: usize) -> Result<(u32, Option<u32>), PatternError> {
+        let min: u32 = self.parse_number(start)?;
+        let max: Option<u32> = if self.peek() == Some(',') {
+            self.next();
+            if self.peek() == Some('}') {
+                None
+            } else {
+                Some(self.parse_number(start)?)
+            }
+        } else {
+            Some(min)
+        };
+        if self.next() != Some('}') {
+            return Err(PatternError::new(start, "unclosed repetition"));
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err(PatternError::new(start, "invalid repetition bounds"));
+        }
+        Ok((min, max))
+    }
+
+    /// Parses a decimal repetition bound.
+    fn parse_number(&mut self, start: usize) -> Result<u32, PatternError> {
+        let digits_start: usize = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.next();
+        }
+        self.pattern[digits_start..self.pos]
+            .parse::<u32>()
+            .ok()
+            .filter(|n| *n <= MAX_INSTRUCTIONS as u32)
+            .ok_or_else(|| PatternError::new(start, "invalid repetition bound"))
+    }
+
+    /// Parses an atom: a group, class, escape, `.`, or literal char.
+    fn parse_atom(&mut self) -> Result<Node, PatternError> {
+        let start: usize = self.pos;
+        match self.next().unwrap() {
+            '(' => {
+                if self.depth == MAX_DEPTH {
+                    return Err(PatternError::new(start, "pattern nested too deeply"));
+                }
+                self.depth += 1;
+                let node: Node = self.parse_alternate()?;
+                self.depth -= 1;
+                if self.next() != Some(')') {
+                    return Err(PatternError::new(start, "unclosed group"));
+                }
+                Ok(node)
+            }
+            '[' => self.parse_class(start).map(Node::Class),
+            '.' => Ok(Node::Class(Class::char('\n').negate())),
+            '\\' => self.parse_escape(start).map(Node::Class),
+            '*' | '+' | '?' | '{' => Err(PatternError::new(start, "nothing to repeat")),
+            c => Ok(Node::Class(Class::char(c))),
+        }
+    }
+
+    /// Parses an escape after the `\` at `start`.
+    fn parse_escape(&mut self, start: usize) -> Result<Class, PatternError> {
+        let digit: Class = Class::new(vec![('0' as u32, '9' as u32)]);
+        let word: Class = Class::new(vec![
+            ('0' as u32, '9' as u32),
+            ('A' as u32, 'Z' as u32),
+            ('_' as u32, '_' as u32),
+            ('a' as u32, 'z' as u32),
+        ]);
+        let space: Class = Class::new(
+            " \t\n\r\x0C"
+                .chars()
+                .map(|c| (c as u32, c as u32))
+                .collect(),
+        );
+        match self.next() {
+            Some('d') => Ok(digit),
+            Some('D') => Ok(digit.negate()),
+            Some('w') => Ok(word),
+            Some('W') => Ok(word.negate()),
+            Some('s') => Ok(space),
+            Some('S') => Ok(space.negate()),
+            Some('n') => Ok(Class::char('\n')),
+            Some('r') => Ok(Class::char('\r')),
+            Some('t') => Ok(Class::char('\t')),
+            Some('0') => Ok(Class::char('\0')),
+            Some(c) if c.is_ascii_punctuation() => Ok(Class::char(c)),
+            Some(_) => Err(PatternError::new(start, "unknown escape")),
+            None => Err(PatternError::new(start, "incomplete escape")),
+        }
+    }
+
+    /// Parses a char class after the `[` at `start`.
+    fn parse_class(&mut self, start: usize) -> Result<Class, PatternError> {
+        let negated: bool = self.peek() == Some('^');
+        if negated {
+            self.next();
+        }
+
+        let mut ranges: Vec<(u32, u32)> = Vec::default();
+        let mut first: bool = true;
+        loop {
+            let item_start: usize = self.pos;
+            let lo: Class = match self.next() {
+                None => return Err(PatternError::new(start, "unclosed class")),
+                Some(']') if !first => break,
+                Some('\\') => self.parse_escape(item_start)?,
+                Some(c) => Class::char(c),
+            };
+            first = false;
+
+            let is_range: bool = self.peek() == Some('-')
+                && self.pos + 1 < self.pattern.len()
+                && !self.pattern[self.pos + 1..].starts_with(']');
+            match lo.single() {
+                Some(lo) if is_range => {
+                    self.next();
+                    let hi_start: usize = self.pos;
+                    let hi: Class = match self.next().unwrap() {
+                        '\\' => self.parse_escape(hi_start)?,
+                        c => Class::char(c),
+                    };
+                    match hi.single() {
+                        Some(hi) if hi >= lo => ranges.push((lo, hi)),
+                        _ => return Err(PatternError::new(item_start, "invalid class range")),
+                    }
+                }
+                _ => ranges.extend(lo.ranges),
+            }
+        }
+
+        let class: Class = Class::new(ranges);
+        Ok(if negated { class.negate() } else { class })
+    }
+}
+
+/// Matches the longest prefix described by a [Pattern].
+///
+/// The pattern is compiled on first use and shared by every lexer built from the expansion.
+///
+/// # Panics
+/// Panics on first use if the pattern is invalid.
+///
+/// # Example
+/// ```
+/// use lex::lexer::Matcher;
+/// use lex::pattern;
+///
+/// let matcher = pattern!("[a-z][a-z0-9-]*");
+/// assert_eq!(matcher.try_match("foo-2 bar"), Some(5));
+/// assert_eq!(matcher.try_match("2foo"), None);
+/// ```
+#[macro_export]
+macro_rules! pattern {
+    ($s:literal) => {{
+        static PATTERN: ::std::sync::OnceLock<$crate::lexer::matchers::Pattern> =
+            ::std::sync::OnceLock::new();
+        PATTERN.get_or_init(|| match $crate::lexer::matchers::Pattern::new($s) {
+            Ok(pattern) => pattern,
+            Err(e) => panic!("invalid pattern {:?}: {}", $s, e),
+        })
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_pattern() {
+        let test_cases: &[(&str, &str, Option<usize>)] = &[
+            ("[0-9a-fA-F]+", "", None),
+            ("[0-9a-fA-F]+", "1fG", Some(2)),
+            ("[a-z][a-z0-9-]*", "foo-2 bar", Some(5)),
+            ("[a-z][a-z0-9-]*", "2foo", None),
+            ("#[0-9]{6}", "#123456", Some(7)),
+            ("#[0-9]{6}", "#12345", None),
+            ("a{2,3}", "aaaa", Some(3)),
+            ("a{2,}", "aaaa", Some(4)),
+            ("a{0,1}b", "b", Some(1)),
+            ("(ab|a)(bc)?", "abc", Some(3)),
+            ("if|ifx", "ifxy", Some(3)),
+            ("a*", "bbb", None),
+            ("(a*)*b", "aab", Some(3)),
+            ("\\d+\\.\\d+", "3.14;", Some(4)),
+            ("\\w+", "foo_1 x", Some(5)),
+            ("\\s+", " \t\nx", Some(3)),
+            ("[^\"]*\"", "abc\"", Some(4)),
+            (".+", "ab\ncd", Some(2)),
+            ("é+", "éé!", Some(4)),
+            ("[-a]+", "-a-b", Some(3)),
+            ("[a-]+", "a--", Some(3)),
+            ("[]a]+", "]a]", Some(3)),
+            ("\\[\\]", "[]", Some(2)),
+        ];
+
+        for (pattern, source, expected) in test_cases {
+            let matcher: Pattern = Pattern::new(pattern).unwrap();
+            assert_eq!(
+                matcher.try_match(source),
+                *expected,
+                "pattern: {:?}, source: {:?}",
+                pattern,
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn fn_pattern_error() {
+        let test_cases: &[(&str, usize)] = &[
+            ("(a", 0),
+            ("a)", 1),
+            ("[a", 0),
+            ("*a", 0),
+            ("a{2", 1),
+            ("a{3,2}", 1),
+            ("[z-a]", 1),
+            ("\\q", 0),
+            ("a\\", 1),
+            ("a{99999}", 1),
+            ("((){10000}){10000}", 0),
+            ("(a{100}){101}", 0),
+        ];
+
+        for (pattern, offset) in test_cases {
+            let error: PatternError = Pattern::new(pattern).unwrap_err();
+            assert_eq!(error.offset(), *offset, "pattern: {:?}", pattern);
+        }
+
+        let nested: String = format!("{}a{}", "(".repeat(1000), ")".repeat(1000));
+        assert_eq!(Pattern::new(&nested).unwrap_err().offset(), MAX_DEPTH);
+        let repeated: String = format!("a{}", "?".repeat(1000));
+        assert!(Pattern::new(&repeated).is_err());
+        assert!(Pattern::new("[a-z]{9000}").is_ok());
+    }
+
+    #[test]
+    fn first_bytes() {
+        let matcher: Pattern = Pattern::new("x?[0-9]").unwrap();
+        let bytes: Vec<u8> = matcher.first_bytes().unwrap().iter().collect();
+        assert_eq!(bytes, b"0123456789x");
+
+        let matcher: Pattern = Pattern::new("é|a").unwrap();
+        let set: ByteSet = matcher.first_bytes().unwrap();
+        assert!(set.contains(b'a'));
+        assert!(set.contains(0xC3));
+        assert!(!set.contains(b'b'));
+    }
+
+    #[test]
+    fn macro_pattern() {
+        let matcher: &Pattern = pattern!("[a-z]+");
+        assert_eq!(matcher.try_match("abc1"), Some(3));
+    }
+}
@@ -0,0 +1,42 @@
+use std::fmt::{Display, Formatter};
+
+/// A pattern syntax error.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PatternError {
+    offset: usize,
+    message: String,
+}
+
+impl PatternError {
+    //! Construction
+
+    /// Creates a new pattern error.
+    pub fn new(offset: usize, message: impl Into<String>) -> Self {
+        Self {
+            offset,
+            message: message.into(),
+        }
+    }
+}
+
+impl PatternError {
+    //! Properties
+
+    /// Gets the byte offset of the error in the pattern.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Gets the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for PatternError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "pattern byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for PatternError {}
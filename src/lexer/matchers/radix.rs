@@ -0,0 +1,30 @@
+/// The radix of a number literal.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum Radix {
+    /// Base 2, `0b` prefixed.
+    Binary,
+
+    /// Base 8, `0o` prefixed.
+    Octal,
+
+    /// Base 10, not prefixed.
+    #[default]
+    Decimal,
+
+    /// Base 16, `0x` prefixed.
+    Hexadecimal,
+}
+
+impl Radix {
+    //! Properties
+
+    /// Gets the numeric base.
+    pub fn base(&self) -> u32 {
+        match self {
+            Self::Binary => 2,
+            Self::Octal => 8,
+            Self::Decimal => 10,
+            Self::Hexadecimal => 16,
+        }
+    }
+}
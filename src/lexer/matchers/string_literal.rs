@@ -0,0 +1,286 @@
+use crate::lexer::matchers::StringMatch;
+use crate::lexer::{ByteSet, Matcher};
+
+/// A configurable string literal matcher.
+///
+/// By default matches `"` quoted strings with backslash escapes that do not span lines. Other
+/// quote chars, raw strings, triple-quoted strings and newlines are enabled with the builder
+/// methods.
+///
+/// Unterminated strings still match, up to the end of the line or source, so they lex as a single
+/// token. [StringMatch::is_terminated] tells them apart and [Lexer::lex_diagnostics] reports them.
+///
+/// # Example
+/// ```
+/// use lex::lexer::Matcher;
+/// use lex::lexer::matchers::StringLiteral;
+///
+/// let string: StringLiteral = StringLiteral::default().with_raw('r');
+/// assert_eq!(string.try_match(r#""a\"b" + c"#), Some(6));
+/// assert_eq!(string.try_match(r##"r#"say "hi""# + c"##), Some(13));
+///
+/// let m = string.scan("\"abc\nd").unwrap();
+/// assert_eq!((m.len(), m.is_terminated()), (4, false));
+/// ```
+///
+/// [Lexer::lex_diagnostics]: crate::lexer::Lexer::lex_diagnostics
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct StringLiteral {
+    quotes: Vec<char>,
+    escapes: bool,
+    raw_prefix: Option<char>,
+    triple_quotes: bool,
+    newlines: bool,
+}
+
+impl Default for StringLiteral {
+    fn default() -> Self {
+        Self::new('"')
+    }
+}
+
+impl StringLiteral {
+    //! Construction
+
+    /// Creates a new string literal matcher with the `quote` char.
+    pub fn new(quote: char) -> Self {
+        Self {
+            quotes: vec![quote],
+            escapes: true,
+            raw_prefix: None,
+            triple_quotes: false,
+            newlines: false,
+        }
+    }
+}
+
+impl StringLiteral {
+    //! Builders
+
+    /// Adds another `quote` char, like `'`. (builder pattern)
+    #[must_use]
+    pub fn with_quote(mut self, quote: char) -> Self {
+        self.quotes.push(quote);
+        self
+    }
+
+    /// Disables backslash escapes. (builder pattern)
+    #[must_use]
+    pub fn without_escapes(mut self) -> Self {
+        self.escapes = false;
+        self
+    }
+
+    /// Enables raw strings starting with the `prefix`, like `r"..."` or `r#"..."#`.
+    /// (builder pattern)
+    ///
+    /// Raw strings do not process escapes, may span lines, and end at a quote followed by as many
+    /// `#` as the opening fence.
+    #[must_use]
+    pub fn with_raw(mut self, prefix: char) -> Self {
+        self.raw_prefix = Some(prefix);
+        self
+    }
+
+    /// Enables triple-quoted strings, like `"""..."""`, which may span lines. (builder pattern)
+    #[must_use]
+    pub fn with_triple_quotes(mut self) -> Self {
+        self.triple_quotes = true;
+        self
+    }
+
+    /// Allows newlines in quoted strings. (builder pattern)
+    #[must_use]
+    pub fn with_newlines(mut self) -> Self {
+        self.newlines = true;
+        self
+    }
+}
+
+impl StringLiteral {
+    //! Matching
+
+    /// Scans the string literal at the start of the `source`.
+    pub fn scan(&self, source: &str) -> Option<StringMatch> {
+        if let Some(prefix) = self.raw_prefix
+            && let Some(rest) = source.strip_prefix(prefix)
+            && let Some(m) = self.scan_raw(rest)
+        {
+            let offset: usize = prefix.len_utf8();
+            return Some(StringMatch::new(
+                m.len() + offset,
+                m.body_offset() + offset,
+                m.body_len(),
+                true,
+                m.is_terminated(),
+            ));
+        }
+
+        let quote: char = source.chars().next().filter(|c| self.quotes.contains(c))?;
+        let q: usize = quote.len_utf8();
+        if self.triple_quotes
+            && source[q..].starts_with(quote)
+            && source[2 * q..].starts_with(quote)
+        {
+            let body: &str = &source[3 * q..];
+            return Some(match self.find_close(body, quote, 3, true) {
+                Ok(end) => StringMatch::new(3 * q + end + 3 * q, 3 * q, end, false, true),
+                Err(end) => StringMatch::new(3 * q + end, 3 * q, end, false, false),
+            });
+        }
+
+        let body: &str = &source[q..];
+        Some(match self.find_close(body, quote, 1, self.newlines) {
+            Ok(end) => StringMatch::new(q + end + q, q, end, false, true),
+            Err(end) => StringMatch::new(q + end, q, end, false, false),
+        })
+    }
+
+    /// Scans a raw string after its prefix: `#` fences around a quoted body.
+    fn scan_raw(&self, source: &str) -> Option<StringMatch> {
+        let fence: usize = source.bytes().take_while(|b| *b == b'#').count();
+        let quote: char = source[fence..]
+            .chars()
+            .next()
+            .filter(|c| self.quotes.contains(c))?;
+        let start: usize = fence + quote.len_utf8();
+        let body: &str = &source[start..];
+        let mut close: String = quote.to_string();
+        close.push_str(&"#".repeat(fence));
+        Some(match body.find(&close) {
+            Some(end) => StringMatch::new(start + end + close.len(), start, end, true, true),
+            None => StringMatch::new(source.len(), start, body.len(), true, false),
+        })
+    }
+
+    /// Finds the end of the `body` before `count` closing `quote` chars.
+    ///
+    /// Returns the end of an unterminated body as an error: the end of the line (before any
+    /// `\r\n`) unless `newlines` are allowed, or the end of the body.
+    fn find_close(
+        &self,
+        body: &str,
+        quote: char,
+        count: usize,
+        newlines: bool,
+    ) -> Result<usize, usize> {
+        // the end of the last escape sequence, which may end in a `\r`
+        let mut escaped: usize = 0;
+        let mut chars = body.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c == quote
+                && body[i..]
+                    .chars()
+                    .take(count)
+                    .filter(|c| *c == quote)
+                    .count()
+                    == count
+            {
+                return Ok(i);
+            } else if c == '\\' && self.escapes {
+                escaped = chars.next().map_or(body.len(), |(i, c)| i + c.len_utf8());
+            } else if c == '\n' && !newlines {
+                let cr: bool = i > escaped && body[..i].ends_with('\r');
+                return Err(if cr { i - 1 } else { i });
+            }
+        }
+        Err(body.len())
+    }
+}
+
+impl Matcher for StringLiteral {
+    fn try_match(&self, source: &str) -> Option<usize> {
+        self.scan(source).map(|m| m.len())
+    }
+
+    fn first_bytes(&self) -> Option<ByteSet> {
+        let mut set: ByteSet = ByteSet::EMPTY;
+        for c in self.quotes.iter().chain(&self.raw_prefix) {
+            let mut buffer: [u8; 4] = [0; 4];
+            set = set.with_byte(c.encode_utf8(&mut buffer).as_bytes()[0]);
+        }
+        Some(set)
+    }
+
+    fn diagnose(&self, text: &str) -> Option<String> {
+        self.scan(text)
+            .filter(|m| !m.is_terminated())
+            .map(|_| "unterminated string".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_string_literal() {
+        let string: StringLiteral = StringLiteral::default()
+            .with_quote('\'')
+            .with_raw('r')
+            .with_triple_quotes();
+
+        let test_cases: &[(&str, Option<StringMatch>)] = &[
+            ("", None),
+            ("abc", None),
+            ("r", None),
+            ("r#abc", None),
+            (r#""""#, Some(StringMatch::new(2, 1, 0, false, true))),
+            (r#""abc" x"#, Some(StringMatch::new(5, 1, 3, false, true))),
+            (r#"'a"b'"#, Some(StringMatch::new(5, 1, 3, false, true))),
+            (r#""a\"b""#, Some(StringMatch::new(6, 1, 4, false, true))),
+            (r#""a\\"b"#, Some(StringMatch::new(5, 1, 3, false, true))),
+            ("\"abc", Some(StringMatch::new(4, 1, 3, false, false))),
+            ("\"ab\ncd\"", Some(StringMatch::new(3, 1, 2, false, false))),
+            (
+                "\"ab\r\ncd\"",
+                Some(StringMatch::new(3, 1, 2, false, false)),
+            ),
+            (
+                "\"a\\\nb\ncd\"",
+                Some(StringMatch::new(5, 1, 4, false, false)),
+            ),
+            ("\"a\\\r\nb", Some(StringMatch::new(4, 1, 3, false, false))),
+            (r##"r"a\""##, Some(StringMatch::new(5, 2, 2, true, true))),
+            (r##"r#"a"b"#"##, Some(StringMatch::new(8, 3, 3, true, true))),
+            (
+                r###"r##"a"#"##"###,
+                Some(StringMatch::new(10, 4, 3, true, true)),
+            ),
+            ("r#\"a\nb", Some(StringMatch::new(6, 3, 3, true, false))),
+            (
+                "\"\"\"a\n\"b\"\"\"",
+                Some(StringMatch::new(10, 3, 4, false, true)),
+            ),
+            ("\"\"\"a\"\"", Some(StringMatch::new(6, 3, 3, false, false))),
+        ];
+
+        for (source, expected) in test_cases {
+            assert_eq!(string.scan(source), *expected, "source: {:?}", source);
+        }
+    }
+
+    #[test]
+    fn fn_string_literal_options() {
+        let test_cases: &[(StringLiteral, &str, Option<usize>)] = &[
+            (StringLiteral::default(), "'a'", None),
+            (StringLiteral::default(), "r\"a\"", None),
+            (StringLiteral::default(), "\"\"\"a\"\"\"", Some(2)),
+            (
+                StringLiteral::default().with_newlines(),
+                "\"a\nb\"",
+                Some(5),
+            ),
+            (
+                StringLiteral::default().without_escapes(),
+                r#""a\"b"#,
+                Some(4),
+            ),
+            (StringLiteral::new('`'), "`a`", Some(3)),
+        ];
+
+        for (string, source, expected) in test_cases {
+            assert_eq!(string.try_match(source), *expected, "source: {:?}", source);
+        }
+    }
+}
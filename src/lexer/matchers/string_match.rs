@@ -0,0 +1,65 @@
+/// A string literal matched by a [StringLiteral] matcher.
+///
+/// [StringLiteral]: crate::lexer::matchers::StringLiteral
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct StringMatch {
+    len: usize,
+    body_offset: usize,
+    body_len: usize,
+    raw: bool,
+    terminated: bool,
+}
+
+impl StringMatch {
+    //! Construction
+
+    /// Creates a new string match.
+    pub fn new(
+        len: usize,
+        body_offset: usize,
+        body_len: usize,
+        raw: bool,
+        terminated: bool,
+    ) -> Self {
+        Self {
+            len,
+            body_offset,
+            body_len,
+            raw,
+            terminated,
+        }
+    }
+}
+
+impl StringMatch {
+    //! Properties
+
+    /// Gets the length of the literal, including the delimiters.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Gets the offset of the body (the text between the delimiters) from the start of the literal.
+    pub fn body_offset(&self) -> usize {
+        self.body_offset
+    }
+
+    /// Gets the length of the body.
+    pub fn body_len(&self) -> usize {
+        self.body_len
+    }
+
+    /// Checks if the literal is a raw string. (escapes are not processed)
+    pub fn is_raw(&self) -> bool {
+        self.raw
+    }
+
+    /// Checks if the literal has its closing delimiter.
+    ///
+    /// An unterminated literal extends to the end of the line (if newlines are not allowed) or the
+    /// end of the source.
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
@@ -0,0 +1,42 @@
+use crate::lexer::matchers::xid::{is_xid_continue, is_xid_start};
+
+/// Matches a Unicode identifier: `[XID_Start_][XID_Continue]*`. (UAX #31)
+pub fn unicode_ident(source: &str) -> Option<usize> {
+    let mut chars = source.char_indices();
+    match chars.next() {
+        Some((_, c)) if is_xid_start(c) || c == '_' => {}
+        _ => return None,
+    }
+    let len: usize = chars
+        .find(|(_, c)| !is_xid_continue(*c))
+        .map_or(source.len(), |(i, _)| i);
+    Some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_unicode_ident() {
+        let test_cases: &[(&str, Option<usize>)] = &[
+            ("", None),
+            ("123", None),
+            ("foo", Some(3)),
+            ("_foo1", Some(5)),
+            ("café", Some(5)),
+            ("café au", Some(5)),
+            ("日本語", Some(9)),
+            ("x\u{301}", Some(3)),
+            ("\u{301}x", None),
+            ("αβγ+1", Some(6)),
+            ("a€", Some(1)),
+            ("٣", None),
+            ("x٣", Some(3)),
+        ];
+
+        for (source, expected) in test_cases {
+            assert_eq!(unicode_ident(source), *expected, "source: {:?}", source);
+        }
+    }
+}
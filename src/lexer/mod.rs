@@ -1,14 +1,53 @@
+pub use byte_set::*;
+pub use decoder::*;
+pub use edit::*;
+pub use indentation::*;
+pub use interner::*;
+pub use keywords::*;
 pub use lexer::*;
+pub use match_strategy::*;
+pub use matcher::*;
+pub use mode_stacks::*;
 pub use rule::*;
 pub use span::*;
+pub use stream::*;
+pub use terminators::*;
 pub use token::*;
 pub use token_kind::*;
+pub use tokens::*;
+pub use transition::*;
+pub use trivia_tokens::*;
+pub use unrecognized_policy::*;
+pub use value::*;
+pub use values::*;
 
+mod byte_set;
+mod decoder;
+mod edit;
+mod indentation;
+mod interner;
+mod keywords;
+mod lex_state;
 mod lexer;
+mod literal_trie;
+mod match_strategy;
+mod matched;
+mod matcher;
+mod mode;
+mod mode_stacks;
 mod rule;
 mod span;
+mod stream;
+mod terminators;
 mod token;
 mod token_kind;
+mod tokens;
+mod transition;
+mod trivia_tokens;
+mod unrecognized_policy;
+mod value;
+mod values;
 
+pub mod decoders;
 mod macros;
 pub mod matchers;
@@ -0,0 +1,70 @@
+use crate::lexer::literal_trie::LiteralTrie;
+use crate::lexer::matchers::Literal;
+use crate::lexer::{ByteSet, Rule};
+
+/// A named lexer mode with its own ordered rules.
+#[derive(Clone, Debug)]
+pub(in crate::lexer) struct Mode<K> {
+    pub(in crate::lexer) name: String,
+    pub(in crate::lexer) rules: Vec<Rule<K>>,
+    pub(in crate::lexer) segments: Vec<Segment>,
+
+    /// The candidate segment indices for each first byte, in order.
+    pub(in crate::lexer) dispatch: Vec<Vec<usize>>,
+}
+
+/// A segment of a mode's rules, tried in order.
+#[derive(Clone, Debug)]
+pub(in crate::lexer) enum Segment {
+    /// A single rule by index.
+    Rule(usize),
+
+    /// A run of consecutive literal rules merged into a trie.
+    Literals(LiteralTrie),
+}
+
+impl<K> Mode<K> {
+    //! Construction
+
+    /// Creates a new empty mode.
+    pub(in crate::lexer) fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            rules: Vec::default(),
+            segments: Vec::default(),
+            dispatch: vec![Vec::default(); 256],
+        }
+    }
+}
+
+impl<K> Mode<K> {
+    //! Rules
+
+    /// Adds the `rule`. Literal rules are merged into the trie of a preceding literal run.
+    pub(in crate::lexer) fn push_rule(&mut self, rule: Rule<K>) {
+        let index: usize = self.rules.len();
+        let first_bytes: ByteSet = rule.first_bytes();
+        let literal: Option<&Literal> = rule.literal();
+        match (literal, self.segments.last_mut()) {
+            (Some(literal), Some(Segment::Literals(trie))) => trie.insert(index, literal),
+            (Some(literal), _) => {
+                let mut trie: LiteralTrie = LiteralTrie::default();
+                trie.insert(index, literal);
+                self.segments.push(Segment::Literals(trie));
+            }
+            (None, _) => self.segments.push(Segment::Rule(index)),
+        }
+        self.rules.push(rule);
+        self.dispatch_segment(self.segments.len() - 1, first_bytes);
+    }
+
+    /// Adds the segment at `index` to the dispatch buckets of the `first_bytes`.
+    fn dispatch_segment(&mut self, index: usize, first_bytes: ByteSet) {
+        for byte in first_bytes.iter() {
+            let bucket: &mut Vec<usize> = &mut self.dispatch[byte as usize];
+            if bucket.last() != Some(&index) {
+                bucket.push(index);
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+/// The lexer mode stack at the start of each token of a token sequence.
+///
+/// Recorded by [Lexer::lex_modes] so [Lexer::relex_modes] can restart a lexer with multiple modes
+/// in the middle of the source. Stored as runs of tokens sharing a stack.
+///
+/// [Lexer::lex_modes]: crate::lexer::Lexer::lex_modes
+/// [Lexer::relex_modes]: crate::lexer::Lexer::relex_modes
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct ModeStacks {
+    /// The first token index of each run and the mode stack of its tokens.
+    runs: Vec<(usize, Vec<usize>)>,
+}
+
+impl ModeStacks {
+    //! Recording
+
+    /// Records the mode `stack` at the start of the token at `index`.
+    ///
+    /// Indexes must be recorded in increasing order.
+    pub(in crate::lexer) fn record(&mut self, index: usize, stack: &[usize]) {
+        debug_assert!(self.runs.last().is_none_or(|(last, _)| *last <= index));
+
+        match self.runs.last_mut() {
+            Some((_, last)) if last == stack => {}
+            Some((last, last_stack)) if *last == index => *last_stack = stack.to_vec(),
+            _ => self.runs.push((index, stack.to_vec())),
+        }
+    }
+
+    /// Records the stacks of the `other` tokens from `from` onwards, starting at token `index`.
+    pub(in crate::lexer) fn record_from(&mut self, other: &Self, from: usize, index: usize) {
+        self.record(index, other.get(from));
+        let start: usize = other.runs.partition_point(|(i, _)| *i <= from);
+        for (i, stack) in &other.runs[start..] {
+            self.record(i - from + index, stack);
+        }
+    }
+
+    /// Gets the stacks of the first `len` tokens.
+    pub(in crate::lexer) fn prefix(&self, len: usize) -> Self {
+        let end: usize = self.runs.partition_point(|(i, _)| *i < len);
+        Self {
+            runs: self.runs[..end].to_vec(),
+        }
+    }
+}
+
+impl ModeStacks {
+    //! Access
+
+    /// Gets the mode stack at the start of the token at `index`. The last mode is active.
+    pub(in crate::lexer) fn get(&self, index: usize) -> &[usize] {
+        let run: usize = self.runs.partition_point(|(i, _)| *i <= index);
+        debug_assert!(run > 0);
+
+        &self.runs[run - 1].1
+    }
+}
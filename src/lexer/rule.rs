@@ -1,26 +1,211 @@
-/// A lexer rule. Maps a matcher function to a kind of lexical token.
-#[derive(Copy, Clone, Debug)]
+use crate::lexer::matchers::Literal;
+use crate::lexer::{ByteSet, Decoder, Interner, Keywords, Matcher, Transition, Value};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// A lexer rule. Maps a matcher to a kind of lexical token.
+///
+/// Rules are not `Copy` since they can own matchers, decoders and guards. Tables of rules can
+/// still be declared as constants with [Rule::from_fn].
+#[derive(Clone)]
 pub struct Rule<K> {
     kind: K,
-    matcher: fn(&str) -> Option<usize>,
+    matcher: RuleMatcher,
+    transition: Option<Transition>,
+    first_bytes: Option<ByteSet>,
+    decoder: Option<Arc<dyn Decoder>>,
+    keywords: Option<Arc<Keywords<K>>>,
+    guard: Option<Arc<Guard<K>>>,
+}
+
+/// A rule guard. Decides if a rule is tried given the kind of the previous significant token.
+type Guard<K> = dyn Fn(Option<K>) -> bool + Send + Sync;
+
+/// A rule matcher: a plain fn pointer, or any shared matcher.
+#[derive(Clone)]
+enum RuleMatcher {
+    Fn(fn(&str) -> Option<usize>),
+    Shared(Arc<dyn Matcher>),
+}
+
+impl RuleMatcher {
+    //! Access
+
+    /// Gets the matcher.
+    fn get(&self) -> &dyn Matcher {
+        match self {
+            Self::Fn(matcher) => matcher,
+            Self::Shared(matcher) => matcher.as_ref(),
+        }
+    }
 }
 
 impl<K> Rule<K> {
     //! Construction
 
     /// Creates a new rule.
-    pub const fn new(kind: K, matcher: fn(&str) -> Option<usize>) -> Self {
-        Self { kind, matcher }
+    pub fn new(kind: K, matcher: impl Matcher + 'static) -> Self {
+        Self {
+            kind,
+            matcher: RuleMatcher::Shared(Arc::new(matcher)),
+            transition: None,
+            first_bytes: None,
+            decoder: None,
+            keywords: None,
+            guard: None,
+        }
+    }
+
+    /// Creates a new rule from a fn pointer `matcher`. Usable in constants.
+    ///
+    /// # Example
+    /// ```
+    /// use lex::lexer::Rule;
+    /// use lex::lexer::matchers::{digits, whitespace};
+    ///
+    /// const RULES: [Rule<u8>; 2] = [Rule::from_fn(0, whitespace), Rule::from_fn(1, digits)];
+    /// ```
+    pub const fn from_fn(kind: K, matcher: fn(&str) -> Option<usize>) -> Self {
+        Self {
+            kind,
+            matcher: RuleMatcher::Fn(matcher),
+            transition: None,
+            first_bytes: None,
+            decoder: None,
+            keywords: None,
+            guard: None,
+        }
+    }
+}
+
+impl<K> Rule<K> {
+    //! Transitions
+
+    /// Sets the mode `transition` applied when the rule matches. (builder pattern)
+    #[must_use]
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.transition = Some(transition);
+        self
+    }
+
+    /// Pushes the `mode` when the rule matches. (builder pattern)
+    #[must_use]
+    pub fn with_push(self, mode: impl Into<String>) -> Self {
+        self.with_transition(Transition::Push(mode.into()))
+    }
+
+    /// Pops the current mode when the rule matches. (builder pattern)
+    #[must_use]
+    pub fn with_pop(self) -> Self {
+        self.with_transition(Transition::Pop)
+    }
+
+    /// Switches to the `mode` when the rule matches. (builder pattern)
+    #[must_use]
+    pub fn with_switch(self, mode: impl Into<String>) -> Self {
+        self.with_transition(Transition::Switch(mode.into()))
+    }
+
+    /// Gets the mode transition.
+    pub fn transition(&self) -> Option<&Transition> {
+        self.transition.as_ref()
     }
 }
 
 impl<K: Copy> Rule<K> {
     //! Properties
 
-    /// Gets the token kind.
-    pub(in crate::lexer) fn kind(self) -> K {
+    /// Gets the rule kind.
+    pub(in crate::lexer) fn kind(&self) -> K {
         self.kind
     }
+
+    /// Gets the token kind for the matched `text`. Keywords take precedence over the rule kind.
+    pub(in crate::lexer) fn classify(&self, text: &str) -> K {
+        self.keywords
+            .as_ref()
+            .and_then(|keywords| keywords.get(text))
+            .unwrap_or(self.kind)
+    }
+}
+
+impl<K> Rule<K> {
+    //! Keywords
+
+    /// Sets the `keywords` that classify matched text as keyword kinds. (builder pattern)
+    #[must_use]
+    pub fn with_keywords(mut self, keywords: impl Into<Arc<Keywords<K>>>) -> Self {
+        self.keywords = Some(keywords.into());
+        self
+    }
+}
+
+impl<K> Rule<K> {
+    //! Guards
+
+    /// Only tries the rule if the `guard` accepts the kind of the previous significant token.
+    /// (builder pattern)
+    ///
+    /// The previous kind is `None` at the start of the source. Trivia tokens are skipped, see
+    /// [Lexer::add_trivia]. Guarded rules are not merged into literal tries.
+    ///
+    /// [Lexer::add_trivia]: crate::lexer::Lexer::add_trivia
+    #[must_use]
+    pub fn with_guard(mut self, guard: impl Fn(Option<K>) -> bool + Send + Sync + 'static) -> Self {
+        self.guard = Some(Arc::new(guard));
+        self
+    }
+
+    /// Checks if the rule has a guard.
+    pub fn is_guarded(&self) -> bool {
+        self.guard.is_some()
+    }
+}
+
+impl<K: Copy> Rule<K> {
+    //! Guards
+
+    /// Checks if the guard allows the rule after the `previous` significant token kind.
+    pub(in crate::lexer) fn allows(&self, previous: Option<K>) -> bool {
+        self.guard.as_ref().is_none_or(|guard| guard(previous))
+    }
+}
+
+impl<K> Rule<K> {
+    //! Dispatch
+
+    /// Declares the set of bytes every match can start with. (builder pattern)
+    ///
+    /// Overrides the set declared by the matcher. The rule is only tried at positions starting
+    /// with one of these bytes.
+    #[must_use]
+    pub fn with_first_bytes(mut self, first_bytes: ByteSet) -> Self {
+        self.first_bytes = Some(first_bytes);
+        self
+    }
+
+    /// Gets the set of bytes every match can start with. (`ByteSet::ALL` if unknown)
+    pub fn first_bytes(&self) -> ByteSet {
+        self.first_bytes
+            .or_else(|| self.matcher.get().first_bytes())
+            .unwrap_or(ByteSet::ALL)
+    }
+}
+
+impl<K> Rule<K> {
+    //! Values
+
+    /// Sets the `decoder` that decodes the value of matched tokens. (builder pattern)
+    #[must_use]
+    pub fn with_decoder(mut self, decoder: impl Decoder + 'static) -> Self {
+        self.decoder = Some(Arc::new(decoder));
+        self
+    }
+
+    /// Decodes the value of a token with the matched `text`.
+    pub(in crate::lexer) fn decode(&self, text: &str, interner: &mut Interner) -> Option<Value> {
+        self.decoder.as_ref()?.decode(text, interner)
+    }
 }
 
 impl<K> Rule<K> {
@@ -28,6 +213,42 @@ impl<K> Rule<K> {
 
     /// Attempts to match the `source`. Returns the number of bytes consumed.
     pub(in crate::lexer) fn try_match(&self, source: &str) -> Option<usize> {
-        (self.matcher)(source)
+        self.matcher.get().try_match(source)
+    }
+
+    /// Gets the literal the matcher is equivalent to, if any. (`None` for guarded rules)
+    pub(in crate::lexer) fn literal(&self) -> Option<&Literal> {
+        self.matcher
+            .get()
+            .literal()
+            .filter(|_| self.guard.is_none())
+    }
+
+    /// Diagnoses the matched `text`. Returns an error message if the match is malformed.
+    pub(in crate::lexer) fn diagnose(&self, text: &str) -> Option<String> {
+        self.matcher.get().diagnose(text)
+    }
+
+    /// Checks if matches may defer a body to the following lines.
+    pub(in crate::lexer) fn defers_bodies(&self) -> bool {
+        self.matcher.get().defers_bodies()
+    }
+
+    /// Gets the matcher of the body the matched `text` defers to the following lines, if any.
+    pub(in crate::lexer) fn deferred_body(&self, text: &str) -> Option<Arc<dyn Matcher>> {
+        self.matcher.get().deferred_body(text)
+    }
+}
+
+impl<K: Debug> Debug for Rule<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Rule")
+            .field("kind", &self.kind)
+            .field("transition", &self.transition)
+            .field("first_bytes", &self.first_bytes)
+            .field("decoder", &self.decoder.is_some())
+            .field("keywords", &self.keywords)
+            .field("guard", &self.guard.is_some())
+            .finish()
     }
 }
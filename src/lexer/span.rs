@@ -45,6 +45,18 @@ impl Span {
     }
 }
 
+impl Span {
+    //! Shifting
+
+    /// Gets the span moved by `delta` bytes.
+    pub const fn shift(self, delta: i64) -> Self {
+        debug_assert!(self.offset as i64 + delta >= 0);
+        debug_assert!(self.offset as i64 + delta <= u32::MAX as i64);
+
+        Self::new((self.offset as i64 + delta) as u32, self.len)
+    }
+}
+
 impl Span {
     //! Line & Column
 
@@ -100,6 +112,13 @@ mod tests {
         assert_eq!(Span::new(0, 11).text(source), "hello world");
     }
 
+    #[test]
+    fn shift() {
+        assert_eq!(Span::new(4, 2).shift(3), Span::new(7, 2));
+        assert_eq!(Span::new(4, 2).shift(-4), Span::new(0, 2));
+        assert_eq!(Span::new(4, 2).shift(0), Span::new(4, 2));
+    }
+
     #[test]
     fn line_column() {
         let source: &str = "ab\ncd\nef";
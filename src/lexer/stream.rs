@@ -0,0 +1,331 @@
+use crate::lexer::lex_state::LexState;
+use crate::lexer::{Lexer, TokenKind};
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Error, ErrorKind};
+
+/// A lexical token from a stream. Uses absolute 64-bit byte offsets.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct StreamToken<K> {
+    kind: K,
+    offset: u64,
+    len: u64,
+}
+
+impl<K> StreamToken<K> {
+    //! Construction
+
+    /// Creates a new stream token.
+    pub const fn new(kind: K, offset: u64, len: u64) -> Self {
+        Self { kind, offset, len }
+    }
+}
+
+impl<K: Copy> StreamToken<K> {
+    //! Properties
+
+    /// Gets the token kind.
+    pub const fn kind(self) -> K {
+        self.kind
+    }
+
+    /// Gets the absolute byte offset. (0-indexed)
+    pub const fn offset(self) -> u64 {
+        self.offset
+    }
+
+    /// Gets the number of bytes.
+    pub const fn len(self) -> u64 {
+        self.len
+    }
+
+    /// Gets the absolute end byte offset. (`offset + len`)
+    pub const fn end(self) -> u64 {
+        self.offset + self.len
+    }
+
+    /// Checks if the token is empty.
+    pub const fn is_empty(self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<K: Display> Display for StreamToken<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "token[{}, span[offset={}, len={}]]",
+            self.kind, self.offset, self.len
+        )
+    }
+}
+
+/// A streaming lexer over a [BufRead]. Ends with the `EndOfFile` token.
+///
+/// Reads the input in chunks and keeps at least one chunk of lookahead past the start of each
+/// token. Consumed input is discarded once it exceeds a chunk, so memory is bounded by a few
+/// chunks plus twice the longest token. Tokens reaching the end of the buffered input are matched
+/// again once the lookahead is doubled. Matchers must not look more than one chunk past the end of
+/// their match.
+///
+/// Created by [Lexer::stream].
+pub struct StreamTokens<'a, K, R> {
+    lexer: &'a Lexer<K>,
+    reader: R,
+    chunk_size: usize,
+    state: LexState<K>,
+    buf: String,
+    buf_offset: u64,
+    pos: usize,
+    last_len: usize,
+    pending: Vec<u8>,
+    eof: bool,
+    done: bool,
+}
+
+impl<'a, K, R: BufRead> StreamTokens<'a, K, R> {
+    //! Construction
+
+    /// The default chunk size in bytes.
+    pub const DEFAULT_CHUNK_SIZE: usize = 8 * 1024;
+
+    /// Creates a new streaming lexer.
+    pub(in crate::lexer) fn new(lexer: &'a Lexer<K>, reader: R) -> Self {
+        Self {
+            lexer,
+            reader,
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+            state: LexState::default(),
+            buf: String::default(),
+            buf_offset: 0,
+            pos: 0,
+            last_len: 0,
+            pending: Vec::default(),
+            eof: false,
+            done: false,
+        }
+    }
+
+    /// Sets the chunk size in bytes. (builder pattern)
+    #[must_use]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        debug_assert!(chunk_size > 0);
+
+        self.chunk_size = chunk_size;
+        self
+    }
+}
+
+impl<K, R> StreamTokens<'_, K, R> {
+    //! Properties
+
+    /// Gets the text of the last token returned by `next_token`.
+    pub fn text(&self) -> &str {
+        &self.buf[self.pos - self.last_len..self.pos]
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind, R: BufRead> StreamTokens<'_, K, R> {
+    //! Lexing
+
+    /// Lexes the next token. Returns `None` after the `EndOfFile` token.
+    ///
+    /// The token text is available from `text` until the next call.
+    pub fn next_token(&mut self) -> Result<Option<StreamToken<K>>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+
+        self.last_len = 0;
+        if self.pos > self.chunk_size {
+            self.buf.drain(..self.pos);
+            self.buf_offset += self.pos as u64;
+            self.pos = 0;
+        }
+
+        while !self.eof && self.buf.len() - self.pos < self.chunk_size {
+            self.read_chunk()?;
+        }
+
+        loop {
+            let remaining: &str = &self.buf[self.pos..];
+            let offset: u64 = self.buf_offset + self.pos as u64;
+            if remaining.is_empty() {
+                self.done = true;
+                return Ok(Some(StreamToken::new(K::end_of_file(), offset, 0)));
+            }
+
+            let mut state: LexState<K> = self.state.clone();
+            let (kind, len, _) = self.lexer.step(remaining, &mut state);
+            debug_assert!(len > 0);
+            if len == remaining.len() && !self.eof {
+                // double the lookahead so long tokens are matched a logarithmic number of times
+                let target: usize = 2 * len;
+                while !self.eof && self.buf.len() - self.pos < target {
+                    self.read_chunk()?;
+                }
+                continue;
+            }
+
+            self.state = state;
+            self.pos += len;
+            self.last_len = len;
+            return Ok(Some(StreamToken::new(kind, offset, len as u64)));
+        }
+    }
+
+    /// Reads the next chunk into the buffer. Keeps incomplete UTF-8 sequences pending.
+    fn read_chunk(&mut self) -> Result<(), Error> {
+        let mut read: usize = 0;
+        while read < self.chunk_size {
+            let available: &[u8] = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                self.eof = true;
+                break;
+            }
+            let len: usize = available.len().min(self.chunk_size - read);
+            self.pending.extend_from_slice(&available[..len]);
+            self.reader.consume(len);
+            read += len;
+        }
+
+        let valid: usize = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(e) if e.error_len().is_none() && !self.eof => e.valid_up_to(),
+            Err(e) => return Err(Error::new(ErrorKind::InvalidData, e)),
+        };
+        let text: &str = std::str::from_utf8(&self.pending[..valid]).unwrap();
+        self.buf.push_str(text);
+        self.pending.drain(..valid);
+        Ok(())
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind, R: BufRead> Iterator for StreamTokens<'_, K, R> {
+    type Item = Result<StreamToken<K>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_token() {
+            Ok(token) => token.map(Ok),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::matchers::{digits, ident, whitespace};
+    use crate::lexer::{Lexer, StreamToken, Token};
+    use crate::{keyword, literal};
+    use std::io::{BufReader, ErrorKind};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    crate::lexer! {
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        enum Kind {
+            Whitespace: whitespace,
+            Let: keyword!("let"),
+            Ident: ident,
+            Int: digits,
+            EqEq: literal!("=="),
+            Eq: literal!("="),
+        }
+    }
+
+    #[test]
+    fn stream() {
+        let lexer: Lexer<Kind> = Kind::lexer();
+        let source: &str = "let letter == 12345 é= lets\n";
+        let expected: Vec<Token<Kind>> = lexer.lex(source);
+
+        for chunk_size in 1..=8 {
+            let reader: BufReader<&[u8]> = BufReader::with_capacity(3, source.as_bytes());
+            let mut stream = lexer.stream(reader).with_chunk_size(chunk_size);
+            let mut tokens: Vec<(StreamToken<Kind>, String)> = Vec::default();
+            while let Some(token) = stream.next_token().unwrap() {
+                tokens.push((token, stream.text().to_string()));
+            }
+
+            assert_eq!(tokens.len(), expected.len(), "chunk size: {chunk_size}");
+            for ((token, text), expected) in tokens.iter().zip(&expected) {
+                assert_eq!(token.kind(), expected.kind(), "chunk size: {chunk_size}");
+                assert_eq!(token.offset(), expected.span().offset() as u64);
+                assert_eq!(token.len(), expected.span().len() as u64);
+                assert_eq!(text, expected.text(source));
+            }
+        }
+    }
+
+    #[test]
+    fn stream_long_token() {
+        let attempts: Arc<AtomicUsize> = Arc::default();
+        let counter: Arc<AtomicUsize> = attempts.clone();
+        let lexer: Lexer<Kind> = Lexer::default()
+            .with_rule(Kind::Whitespace, whitespace)
+            .with_rule(Kind::Ident, move |source: &str| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                ident(source)
+            });
+        let source: String = format!("a {} b", "x".repeat(10_000));
+
+        let mut stream = lexer.stream(source.as_bytes()).with_chunk_size(16);
+        let mut tokens: Vec<(StreamToken<Kind>, usize)> = Vec::default();
+        while let Some(token) = stream.next_token().unwrap() {
+            tokens.push((token, stream.text().len()));
+        }
+
+        assert_eq!(
+            tokens,
+            [
+                (StreamToken::new(Kind::Ident, 0, 1), 1),
+                (StreamToken::new(Kind::Whitespace, 1, 1), 1),
+                (StreamToken::new(Kind::Ident, 2, 10_000), 10_000),
+                (StreamToken::new(Kind::Whitespace, 10_002, 1), 1),
+                (StreamToken::new(Kind::Ident, 10_003, 1), 1),
+                (StreamToken::new(Kind::EndOfFile, 10_004, 0), 0),
+            ]
+        );
+        assert!(attempts.load(Ordering::Relaxed) < 20);
+    }
+
+    #[test]
+    fn stream_iter() {
+        let lexer: Lexer<Kind> = Kind::lexer();
+        let kinds: Vec<Kind> = lexer
+            .stream("a = 1".as_bytes())
+            .map(|token| token.unwrap().kind())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                Kind::Ident,
+                Kind::Whitespace,
+                Kind::Eq,
+                Kind::Whitespace,
+                Kind::Int,
+                Kind::EndOfFile,
+            ]
+        );
+    }
+
+    #[test]
+    fn stream_invalid_utf8() {
+        let lexer: Lexer<Kind> = Kind::lexer();
+        let source: &[u8] = b"a \xFF b";
+        let mut stream = lexer.stream(source);
+        let error = stream.find_map(Result::err).unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(stream.next().is_none());
+
+        let source: &[u8] = b"a \xC3";
+        let mut stream = lexer.stream(source).with_chunk_size(1);
+        assert!(stream.any(|token| token.is_err()));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::lexer::{Span, Token, TokenKind};
+
+/// A terminator insertion pass. Makes significant newlines explicit, as in Go and JavaScript.
+///
+/// When a trivia token containing a line break (e.g. whitespace or a comment) follows a significant
+/// token whose kind is a trigger, a zero-width `terminator` token is inserted after the
+/// significant token. The same happens at the end of the source. With a newline kind, a trivia
+/// token of that kind is reclassified as the terminator instead.
+#[derive(Clone, Debug)]
+pub struct Terminators<K> {
+    terminator: K,
+    triggers: Vec<K>,
+    trivia: Vec<K>,
+    newline: Option<K>,
+}
+
+impl<K> Terminators<K> {
+    //! Construction
+
+    /// Creates a new terminator insertion pass emitting the `terminator` kind.
+    pub fn new(terminator: K) -> Self {
+        Self {
+            terminator,
+            triggers: Vec::default(),
+            trivia: Vec::default(),
+            newline: None,
+        }
+    }
+}
+
+impl<K> Terminators<K> {
+    //! Configuration
+
+    /// Adds a trigger token kind. A line break after a token of this kind ends the statement.
+    pub fn add_trigger(&mut self, kind: K) {
+        self.triggers.push(kind);
+    }
+
+    /// Adds a trigger token kind. (builder pattern)
+    #[must_use]
+    pub fn with_trigger(mut self, kind: K) -> Self {
+        self.add_trigger(kind);
+        self
+    }
+
+    /// Adds a trivia token kind. Only trivia tokens can contain significant line breaks.
+    pub fn add_trivia(&mut self, kind: K) {
+        self.trivia.push(kind);
+    }
+
+    /// Adds a trivia token kind. (builder pattern)
+    #[must_use]
+    pub fn with_trivia(mut self, kind: K) -> Self {
+        self.add_trivia(kind);
+        self
+    }
+
+    /// Sets the trivia kind to reclassify as the terminator when it contains the line break,
+    /// instead of inserting a zero-width token. (builder pattern)
+    #[must_use]
+    pub fn with_newline(mut self, kind: K) -> Self {
+        self.newline = Some(kind);
+        self
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind> Terminators<K> {
+    //! Applying
+
+    /// Adds the terminators to the `tokens` lexed from the `source`.
+    pub fn apply(&self, tokens: &[Token<K>], source: &str) -> Vec<Token<K>> {
+        let mut result: Vec<Token<K>> = Vec::with_capacity(tokens.len());
+
+        // the result index after the previous triggering token, and its end offset
+        let mut pending: Option<(usize, u32)> = None;
+
+        for token in tokens {
+            let kind: K = token.kind();
+            if self.trivia.contains(&kind) {
+                if let Some((index, end)) = pending
+                    && token.text(source).contains('\n')
+                {
+                    if self.newline == Some(kind) {
+                        result.push(Token::new(self.terminator, token.span()));
+                        pending = None;
+                        continue;
+                    }
+                    result.insert(index, Token::new(self.terminator, Span::new(end, 0)));
+                    pending = None;
+                }
+                result.push(*token);
+                continue;
+            }
+
+            if kind == K::end_of_file()
+                && let Some((index, end)) = pending
+            {
+                result.insert(index, Token::new(self.terminator, Span::new(end, 0)));
+            }
+            result.push(*token);
+            pending = self
+                .triggers
+                .contains(&kind)
+                .then(|| (result.len(), token.span().end()));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::matchers::{digits, ident, whitespace};
+    use crate::lexer::{Lexer, Terminators, Token};
+    use crate::{line_comment, literal};
+
+    crate::lexer! {
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        enum Kind {
+            Whitespace: whitespace,
+            Comment: line_comment!("//"),
+            Ident: ident,
+            Int: digits,
+            Eq: literal!("="),
+            Plus: literal!("+"),
+            RParen: literal!(")"),
+            LParen: literal!("("),
+            Semi: literal!(";"),
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let lexer: Lexer<Kind> = Kind::lexer();
+        let terminators: Terminators<Kind> = Terminators::new(Kind::Semi)
+            .with_trivia(Kind::Whitespace)
+            .with_trivia(Kind::Comment)
+            .with_trigger(Kind::Ident)
+            .with_trigger(Kind::Int)
+            .with_trigger(Kind::RParen);
+        let source: &str = "a = f(x) // call\nb +\n  1;\n\nc";
+        let tokens: Vec<Token<Kind>> = terminators.apply(&lexer.lex(source), source);
+
+        let expected: &[(Kind, &str)] = &[
+            (Kind::Ident, "a"),
+            (Kind::Eq, "="),
+            (Kind::Ident, "f"),
+            (Kind::LParen, "("),
+            (Kind::Ident, "x"),
+            (Kind::RParen, ")"),
+            (Kind::Semi, ""),
+            (Kind::Ident, "b"),
+            (Kind::Plus, "+"),
+            (Kind::Int, "1"),
+            (Kind::Semi, ";"),
+            (Kind::Ident, "c"),
+            (Kind::Semi, ""),
+            (Kind::EndOfFile, ""),
+        ];
+
+        let significant: Vec<&Token<Kind>> = tokens
+            .iter()
+            .filter(|t| t.kind() != Kind::Whitespace && t.kind() != Kind::Comment)
+            .collect();
+        assert_eq!(significant.len(), expected.len());
+        for (token, (kind, text)) in significant.iter().zip(expected) {
+            assert_eq!(token.kind(), *kind);
+            assert_eq!(token.text(source), *text);
+        }
+        assert!(tokens.is_sorted_by_key(|t| t.span().offset()));
+    }
+
+    #[test]
+    fn apply_newline() {
+        let lexer: Lexer<Kind> = Kind::lexer();
+        let terminators: Terminators<Kind> = Terminators::new(Kind::Semi)
+            .with_trivia(Kind::Whitespace)
+            .with_trigger(Kind::Ident)
+            .with_newline(Kind::Whitespace);
+        let source: &str = "a \n b +\n c";
+        let tokens: Vec<Token<Kind>> = terminators.apply(&lexer.lex(source), source);
+
+        let kinds: Vec<Kind> = tokens.iter().map(|t| t.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                Kind::Ident,
+                Kind::Semi,
+                Kind::Ident,
+                Kind::Whitespace,
+                Kind::Plus,
+                Kind::Whitespace,
+                Kind::Ident,
+                Kind::Semi,
+                Kind::EndOfFile,
+            ]
+        );
+        assert_eq!(tokens[1].text(source), " \n ");
+    }
+}
@@ -0,0 +1,100 @@
+use crate::lexer::lex_state::LexState;
+use crate::lexer::matched::Matched;
+use crate::lexer::{Lexer, Span, Token, TokenKind};
+use std::iter::FusedIterator;
+
+/// A lazy iterator over the tokens of a source text. Ends with the `EndOfFile` token.
+///
+/// Created by [Lexer::tokens].
+#[derive(Clone, Debug)]
+pub struct Tokens<'a, K> {
+    lexer: &'a Lexer<K>,
+    source: &'a str,
+    pos: usize,
+    state: LexState<K>,
+    done: bool,
+}
+
+impl<'a, K> Tokens<'a, K> {
+    //! Construction
+
+    /// Creates a new token iterator starting at the byte offset `pos` in the default mode.
+    pub(in crate::lexer) fn new(lexer: &'a Lexer<K>, source: &'a str, pos: usize) -> Self {
+        debug_assert!(source.len() <= u32::MAX as usize);
+        debug_assert!(source.is_char_boundary(pos));
+
+        Self {
+            lexer,
+            source,
+            pos,
+            state: LexState::default(),
+            done: false,
+        }
+    }
+
+    /// Sets the kind of the significant token before the start position. (builder pattern)
+    #[must_use]
+    pub(in crate::lexer) fn with_previous(mut self, previous: Option<K>) -> Self {
+        self.state.previous = previous;
+        self
+    }
+
+    /// Sets the mode `stack` at the start position. (builder pattern)
+    #[must_use]
+    pub(in crate::lexer) fn with_stack(mut self, stack: Vec<usize>) -> Self {
+        debug_assert!(!stack.is_empty());
+
+        self.state.stack = stack;
+        self
+    }
+}
+
+impl<K> Tokens<'_, K> {
+    //! Properties
+
+    /// Gets the byte offset of the next token.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Gets the mode stack at the start of the next token.
+    pub(in crate::lexer) fn stack(&self) -> &[usize] {
+        &self.state.stack
+    }
+}
+
+impl<'a, K: Copy + PartialEq + TokenKind> Tokens<'a, K> {
+    //! Lexing
+
+    /// Lexes the next token. Also returns what matched it. (`None` for unrecognized and end-of-file
+    /// tokens)
+    pub(in crate::lexer) fn next_match(&mut self) -> Option<(Token<K>, Option<Matched<'a, K>>)> {
+        if self.done {
+            return None;
+        }
+        if self.pos == self.source.len() {
+            self.done = true;
+            let eof_span: Span = Span::new(self.pos as u32, 0);
+            return Some((Token::new(K::end_of_file(), eof_span), None));
+        }
+
+        let remaining: &str = &self.source[self.pos..];
+        let (kind, len, matched): (K, usize, Option<Matched<'a, K>>) =
+            self.lexer.step(remaining, &mut self.state);
+        debug_assert!(len > 0);
+        debug_assert!(self.source.is_char_boundary(self.pos + len));
+        let span: Span = Span::new(self.pos as u32, len as u32);
+        self.pos += len;
+        Some((Token::new(kind, span), matched))
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind> Iterator for Tokens<'_, K> {
+    type Item = Token<K>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_match().map(|(token, _)| token)
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind> FusedIterator for Tokens<'_, K> {}
@@ -0,0 +1,24 @@
+/// A lexer mode transition. Applied after the rule that carries it matches.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Transition {
+    /// Pushes the named mode onto the mode stack.
+    Push(String),
+
+    /// Pops the current mode off the mode stack. The default mode is never popped.
+    Pop,
+
+    /// Replaces the current mode with the named mode.
+    Switch(String),
+}
+
+impl Transition {
+    //! Properties
+
+    /// Gets the target mode name. (`None` for `Pop`)
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            Self::Push(mode) | Self::Switch(mode) => Some(mode),
+            Self::Pop => None,
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::lexer::Token;
+use std::ops::Range;
+
+/// Significant tokens with their trivia attached as leading and trailing trivia.
+///
+/// The trailing trivia of a token is the trivia that follows it on the same line, including a
+/// final trivia token that ends with the line break. All other trivia leads the next significant
+/// token. The `EndOfFile` token collects the trivia at the end of the source.
+#[derive(Clone, Debug)]
+pub struct TriviaTokens<K> {
+    tokens: Vec<Token<K>>,
+    trivia: Vec<Token<K>>,
+    leading: Vec<Range<usize>>,
+    trailing: Vec<Range<usize>>,
+}
+
+impl<K: Copy> TriviaTokens<K> {
+    //! Construction
+
+    /// Attaches the trivia of the lexed `tokens` of the `source`.
+    pub(in crate::lexer) fn new(
+        tokens: impl IntoIterator<Item = Token<K>>,
+        source: &str,
+        is_trivia: impl Fn(K) -> bool,
+    ) -> Self {
+        let mut result: Self = Self {
+            tokens: Vec::default(),
+            trivia: Vec::default(),
+            leading: Vec::default(),
+            trailing: Vec::default(),
+        };
+        let mut leading_start: usize = 0;
+        let mut trailing_open: bool = false;
+
+        for token in tokens {
+            if !is_trivia(token.kind()) {
+                result.tokens.push(token);
+                result.leading.push(leading_start..result.trivia.len());
+                result
+                    .trailing
+                    .push(result.trivia.len()..result.trivia.len());
+                trailing_open = true;
+                leading_start = result.trivia.len();
+                continue;
+            }
+
+            result.trivia.push(token);
+            if trailing_open {
+                let text: &str = token.text(source);
+                match text.find('\n') {
+                    None => {
+                        result.trailing.last_mut().unwrap().end += 1;
+                        leading_start += 1;
+                    }
+                    Some(newline) if newline == text.len() - 1 => {
+                        result.trailing.last_mut().unwrap().end += 1;
+                        leading_start += 1;
+                        trailing_open = false;
+                    }
+                    Some(_) => trailing_open = false,
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<K> TriviaTokens<K> {
+    //! Properties
+
+    /// Gets the significant tokens. Ends with the `EndOfFile` token.
+    pub fn tokens(&self) -> &[Token<K>] {
+        &self.tokens
+    }
+
+    /// Gets all trivia tokens in source order.
+    pub fn trivia(&self) -> &[Token<K>] {
+        &self.trivia
+    }
+
+    /// Gets the leading trivia of the significant token at `index`.
+    pub fn leading(&self, index: usize) -> &[Token<K>] {
+        &self.trivia[self.leading[index].clone()]
+    }
+
+    /// Gets the trailing trivia of the significant token at `index`.
+    pub fn trailing(&self, index: usize) -> &[Token<K>] {
+        &self.trivia[self.trailing[index].clone()]
+    }
+
+    /// Consumes the trivia tokens and returns the significant tokens.
+    pub fn into_tokens(self) -> Vec<Token<K>> {
+        self.tokens
+    }
+}
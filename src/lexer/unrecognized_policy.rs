@@ -0,0 +1,10 @@
+/// An unrecognized input policy. Decides how input no rule matches is turned into tokens.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum UnrecognizedPolicy {
+    /// Each unrecognized char becomes its own `Unrecognized` token.
+    #[default]
+    PerChar,
+
+    /// Consecutive unrecognized chars are merged into a single `Unrecognized` token.
+    Coalesce,
+}
@@ -0,0 +1,53 @@
+use crate::lexer::Symbol;
+
+/// A decoded token value.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    /// An integer value.
+    Int(u128),
+
+    /// A floating-point value.
+    Float(f64),
+
+    /// A string value. (unescaped)
+    Str(String),
+
+    /// An interned symbol.
+    Symbol(Symbol),
+}
+
+impl Value {
+    //! Properties
+
+    /// Gets the integer value.
+    pub fn as_int(&self) -> Option<u128> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the floating-point value.
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the string value.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Gets the symbol value.
+    pub fn as_symbol(&self) -> Option<Symbol> {
+        match self {
+            Self::Symbol(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::lexer::{Interner, Span, Symbol, Value};
+
+/// A table of decoded token values keyed by token span.
+///
+/// Keyed by span rather than token index so it stays valid when tokens are inserted, e.g. by the
+/// [Indentation] and [Terminators] passes. The halves of a token split by [Parser::split] have
+/// no value, since neither has the original span.
+///
+/// [Indentation]: crate::lexer::Indentation
+/// [Terminators]: crate::lexer::Terminators
+/// [Parser::split]: crate::parser::Parser::split
+#[derive(Clone, Default, Debug)]
+pub struct Values {
+    values: Vec<(Span, Value)>,
+    interner: Interner,
+}
+
+impl Values {
+    //! Construction
+
+    /// Adds the `value` for the token `span`.
+    ///
+    /// Spans must be added in increasing order.
+    pub fn push(&mut self, span: Span, value: Value) {
+        debug_assert!(self.values.last().is_none_or(|(last, _)| *last < span));
+
+        self.values.push((span, value));
+    }
+}
+
+impl Values {
+    //! Access
+
+    /// Gets the value for the token `span`.
+    pub fn get(&self, span: Span) -> Option<&Value> {
+        self.values
+            .binary_search_by_key(&span, |(s, _)| *s)
+            .ok()
+            .map(|i| &self.values[i].1)
+    }
+
+    /// Gets the `(span, value)` pairs in span order.
+    pub fn iter(&self) -> impl Iterator<Item = (Span, &Value)> {
+        self.values.iter().map(|(span, value)| (*span, value))
+    }
+
+    /// Gets the number of values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Checks if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl Values {
+    //! Symbols
+
+    /// Gets the symbol interner.
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+
+    /// Gets the symbol interner. (mutable)
+    pub fn interner_mut(&mut self) -> &mut Interner {
+        &mut self.interner
+    }
+
+    /// Gets the text of the `symbol`.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.interner.resolve(symbol)
+    }
+}
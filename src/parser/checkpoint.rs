@@ -3,14 +3,19 @@
 pub struct Checkpoint {
     pos: usize,
     error_count: usize,
+    edit_count: usize,
 }
 
 impl Checkpoint {
     //! Construction
 
     /// Creates a new checkpoint.
-    pub(in crate::parser) fn new(pos: usize, error_count: usize) -> Self {
-        Self { pos, error_count }
+    pub(in crate::parser) fn new(pos: usize, error_count: usize, edit_count: usize) -> Self {
+        Self {
+            pos,
+            error_count,
+            edit_count,
+        }
     }
 }
 
@@ -26,4 +31,9 @@ impl Checkpoint {
     pub(in crate::parser) fn error_count(self) -> usize {
         self.error_count
     }
+
+    /// Gets the token edit count.
+    pub(in crate::parser) fn edit_count(self) -> usize {
+        self.edit_count
+    }
 }
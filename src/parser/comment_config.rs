@@ -1,6 +1,9 @@
-/// A line comment configuration.
-#[derive(Copy, Clone)]
+/// A comment configuration.
+#[derive(Clone)]
 pub(in crate::parser) struct CommentConfig<K> {
     pub(in crate::parser) kind: K,
-    pub(in crate::parser) delimiter_len: usize,
+    pub(in crate::parser) open_len: usize,
+
+    /// The close delimiter of block comments. (`None` for line comments)
+    pub(in crate::parser) close: Option<String>,
 }
@@ -6,3 +6,4 @@ mod checkpoint;
 mod comment_config;
 mod parse_error;
 mod parser;
+mod token_edit;
@@ -1,5 +1,9 @@
-use crate::lexer::{Span, Token, TokenKind};
+use crate::lexer::decoders::{
+    FLOAT_SUFFIXES, INT_SUFFIXES, NumberError, parse_float_with, parse_int_with, parse_signed_with,
+};
+use crate::lexer::{Span, Symbol, Token, TokenKind, Value, Values};
 use crate::parser::comment_config::CommentConfig;
+use crate::parser::token_edit::TokenEdit;
 use crate::parser::{Checkpoint, ParseError};
 
 /// A parser.
@@ -8,8 +12,11 @@ pub struct Parser<K> {
     tokens: Vec<Token<K>>,
     pos: usize,
     skip: Vec<K>,
-    comment: Option<CommentConfig<K>>,
+    comments: Vec<CommentConfig<K>>,
+    values: Values,
+    number_suffixes: Vec<String>,
     errors: Vec<ParseError>,
+    edits: Vec<TokenEdit<K>>,
 }
 
 impl<K: Copy + PartialEq + TokenKind> Parser<K> {
@@ -24,8 +31,11 @@ impl<K: Copy + PartialEq + TokenKind> Parser<K> {
             tokens,
             pos: 0,
             skip: Vec::default(),
-            comment: None,
+            comments: Vec::default(),
+            values: Values::default(),
+            number_suffixes: Vec::default(),
             errors: Vec::default(),
+            edits: Vec::default(),
         }
     }
 }
@@ -57,49 +67,81 @@ impl<K: Copy + PartialEq> Parser<K> {
 impl<K: Copy + PartialEq> Parser<K> {
     //! Comments
 
-    /// Configures line comment extraction.
+    /// Configures line comment extraction. Replaces the line comment configured before.
+    ///
+    /// Use `add_line_comment` to extract several kinds of line comments.
     #[must_use]
     pub fn with_line_comment(mut self, kind: K, delimiter: &str) -> Self {
-        self.comment = Some(CommentConfig {
+        self.comments.retain(|c| c.close.is_some());
+        self.add_line_comment(kind, delimiter);
+        self
+    }
+
+    /// Adds a kind of line comment to extract.
+    pub fn add_line_comment(&mut self, kind: K, delimiter: &str) {
+        self.comments.push(CommentConfig {
             kind,
-            delimiter_len: delimiter.len(),
+            open_len: delimiter.len(),
+            close: None,
         });
+    }
+
+    /// Configures block comment extraction. Replaces the block comment configured before.
+    ///
+    /// Use `add_block_comment` to extract several kinds of block comments.
+    #[must_use]
+    pub fn with_block_comment(mut self, kind: K, open: &str, close: &str) -> Self {
+        self.comments.retain(|c| c.close.is_none());
+        self.add_block_comment(kind, open, close);
         self
     }
 
+    /// Adds a kind of block comment to extract.
+    pub fn add_block_comment(&mut self, kind: K, open: &str, close: &str) {
+        self.comments.push(CommentConfig {
+            kind,
+            open_len: open.len(),
+            close: Some(close.to_string()),
+        });
+    }
+
     /// Gets the leading comment spans before the current position.
     ///
-    /// Each span covers the text after the delimiter and before the line ending. Walks backward
-    /// through the token stream, skipping whitespace, collecting consecutive comment tokens.
+    /// Each span covers the text between the delimiters: after the open delimiter and before the
+    /// line ending or close delimiter. Walks backward through the token stream, skipping
+    /// whitespace, collecting consecutive comment tokens.
     pub fn leading_comments(&self) -> Vec<Span> {
-        let config: CommentConfig<K> = match self.comment {
-            Some(c) => c,
-            None => return Vec::default(),
-        };
-
         let mut comments: Vec<Span> = Vec::default();
         let mut i: usize = self.pos;
 
         while i > 0 {
             i -= 1;
             let token: Token<K> = self.tokens[i];
-            if self.skip.contains(&token.kind()) && token.kind() != config.kind {
-                continue;
-            }
-            if token.kind() != config.kind {
-                break;
-            }
-
-            let offset: u32 = token.span().offset() + config.delimiter_len as u32;
-            let mut len: u32 = token.span().len() - config.delimiter_len as u32;
-
-            // trim trailing newline
+            let config: Option<&CommentConfig<K>> =
+                self.comments.iter().find(|c| c.kind == token.kind());
+            let config: &CommentConfig<K> = match config {
+                Some(config) => config,
+                None if self.skip.contains(&token.kind()) => continue,
+                None => break,
+            };
+
+            let offset: u32 = token.span().offset() + config.open_len as u32;
+            let mut len: u32 = token.span().len() - config.open_len as u32;
             let text: &str = Span::new(offset, len).text(&self.source);
-            if text.ends_with('\n') {
-                len -= 1;
-            }
-            if Span::new(offset, len).text(&self.source).ends_with('\r') {
-                len -= 1;
+
+            if let Some(close) = &config.close {
+                // strip close delimiter (absent if unterminated)
+                if text.ends_with(close.as_str()) {
+                    len -= close.len() as u32;
+                }
+            } else {
+                // trim trailing newline
+                if text.ends_with('\n') {
+                    len -= 1;
+                }
+                if Span::new(offset, len).text(&self.source).ends_with('\r') {
+                    len -= 1;
+                }
             }
 
             comments.push(Span::new(offset, len));
@@ -145,6 +187,14 @@ impl<K: Copy + PartialEq> Parser<K> {
     pub fn check(&self, kind: K) -> bool {
         self.peek().kind() == kind
     }
+
+    /// Checks if the current token text equals the `keyword` ignoring ASCII case.
+    pub fn check_ignore_case(&self, keyword: &str) -> bool {
+        self.peek()
+            .span()
+            .text(&self.source)
+            .eq_ignore_ascii_case(keyword)
+    }
 }
 
 impl<K: Copy + PartialEq + TokenKind> Parser<K> {
@@ -175,6 +225,18 @@ impl<K: Copy + PartialEq + TokenKind> Parser<K> {
         }
     }
 
+    /// Advances if the current token matches the `kind` and its text equals the `keyword` ignoring
+    /// ASCII case.
+    ///
+    /// Returns `None` without recording an error if it does not match.
+    pub fn accept_ignore_case(&mut self, kind: K, keyword: &str) -> Option<Token<K>> {
+        if self.check(kind) && self.check_ignore_case(keyword) {
+            self.advance()
+        } else {
+            None
+        }
+    }
+
     /// Advances if the current token matches the `kind`.
     ///
     /// Returns `None` and records an auto-generated error if it does not match.
@@ -210,16 +272,223 @@ impl<K: Copy + PartialEq + TokenKind> Parser<K> {
     }
 }
 
-impl<K: Copy + PartialEq> Parser<K> {
+impl<K: Copy> Parser<K> {
+    //! Values
+
+    /// Sets the decoded token `values`. (builder pattern)
+    #[must_use]
+    pub fn with_values(mut self, values: Values) -> Self {
+        self.values = values;
+        self
+    }
+
+    /// Gets the decoded value of the `token`.
+    pub fn value(&self, token: Token<K>) -> Option<&Value> {
+        self.values.get(token.span())
+    }
+
+    /// Gets the text of the `symbol`.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.values.resolve(symbol)
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind> Parser<K> {
+    //! Values
+
+    /// Advances if the current token matches the `kind` and returns its decoded value.
+    ///
+    /// Returns `None` and records an error if the token does not match or has no value.
+    pub fn expect_value(&mut self, kind: K) -> Option<Value> {
+        match self.values.get(self.peek().span()) {
+            Some(value) if self.check(kind) => {
+                let value: Value = value.clone();
+                self.advance();
+                Some(value)
+            }
+            None if self.check(kind) => {
+                self.error(format!("invalid {}", kind.label()));
+                None
+            }
+            _ => self.expect(kind).and(None),
+        }
+    }
+}
+
+impl<K> Parser<K> {
+    //! Numbers
+
+    /// Adds a number literal `suffix` accepted by the number methods, in addition to the Rust
+    /// type suffixes. (e.g. `L`) Added suffixes are not range-checked.
+    pub fn add_number_suffix(&mut self, suffix: impl Into<String>) {
+        self.number_suffixes.push(suffix.into());
+    }
+
+    /// Adds a number literal `suffix` accepted by the number methods. (builder pattern)
+    #[must_use]
+    pub fn with_number_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.add_number_suffix(suffix);
+        self
+    }
+
+    /// Gets the accepted number suffixes: the `defaults` and the added suffixes.
+    fn number_suffixes(&self, defaults: &[&str]) -> Vec<String> {
+        defaults
+            .iter()
+            .map(|suffix| suffix.to_string())
+            .chain(self.number_suffixes.iter().cloned())
+            .collect()
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind> Parser<K> {
+    //! Numbers
+
+    /// Advances if the current token matches the `kind` and parses its text as a `u64`.
+    ///
+    /// Returns `None` and records an error if the token does not match, or on the token if its
+    /// text is malformed or out of range.
+    pub fn expect_u64(&mut self, kind: K) -> Option<u64> {
+        let suffixes: Vec<String> = self.number_suffixes(INT_SUFFIXES);
+        self.expect_number(kind, "integer", "u64", |text| {
+            parse_int_with(text, &suffixes)
+                .and_then(|value| u64::try_from(value).map_err(|_| NumberError::Overflow))
+        })
+    }
+
+    /// Advances if the current token matches the `kind` and parses its text as an `i128`.
+    ///
+    /// Returns `None` and records an error if the token does not match, or on the token if its
+    /// text is malformed or out of range.
+    pub fn expect_i128(&mut self, kind: K) -> Option<i128> {
+        let suffixes: Vec<String> = self.number_suffixes(INT_SUFFIXES);
+        self.expect_number(kind, "integer", "i128", |text| {
+            parse_signed_with(text, &suffixes)
+        })
+    }
+
+    /// Advances if the current token matches the `kind` and parses its text as an `f64`.
+    ///
+    /// Returns `None` and records an error if the token does not match, or on the token if its
+    /// text is malformed or out of range.
+    pub fn expect_f64(&mut self, kind: K) -> Option<f64> {
+        let suffixes: Vec<String> = self.number_suffixes(FLOAT_SUFFIXES);
+        self.expect_number(kind, "float", "f64", |text| {
+            parse_float_with(text, &suffixes)
+        })
+    }
+
+    /// Advances if the current token matches the `kind` and parses its text with `parse`.
+    fn expect_number<T>(
+        &mut self,
+        kind: K,
+        label: &str,
+        type_name: &str,
+        parse: impl Fn(&str) -> Result<T, NumberError>,
+    ) -> Option<T> {
+        let token: Token<K> = self.expect(kind)?;
+        match parse(token.span().text(&self.source)) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                let message: String = match error {
+                    NumberError::Malformed => format!("malformed {label} literal"),
+                    NumberError::Overflow => {
+                        format!("{label} literal out of range for {type_name}")
+                    }
+                };
+                self.errors.push(ParseError::new(token.span(), message));
+                None
+            }
+        }
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind> Parser<K> {
+    //! Token Edits
+
+    /// Changes the kind of the current token to `kind`.
+    ///
+    /// Returns `false` and does nothing at the end of the file. The change is undone by restoring
+    /// a checkpoint created before it.
+    pub fn reclassify(&mut self, kind: K) -> bool {
+        let original: Token<K> = self.peek();
+        if original.kind() == K::end_of_file() {
+            return false;
+        }
+
+        self.tokens[self.pos] = Token::new(kind, original.span());
+        self.edits.push(TokenEdit::Reclassify {
+            pos: self.pos,
+            original,
+        });
+        self.skip_ignored();
+        true
+    }
+
+    /// Splits the current token at the byte `offset` into a `first` token and a `second` token.
+    ///
+    /// The `offset` is relative to the token start and must be inside the token on a char
+    /// boundary. The parser stays on the `first` token. (e.g. splitting `>>` into `>` and `>`)
+    /// Neither half has a decoded value. The change is undone by restoring a checkpoint created
+    /// before it.
+    ///
+    /// Returns `false` and does nothing if the `offset` is not inside the token on a char
+    /// boundary, or at the end of the file.
+    pub fn split(&mut self, offset: u32, first: K, second: K) -> bool {
+        let original: Token<K> = self.peek();
+        let span: Span = original.span();
+        if original.kind() == K::end_of_file()
+            || offset == 0
+            || offset >= span.len()
+            || !self
+                .source
+                .is_char_boundary((span.offset() + offset) as usize)
+        {
+            return false;
+        }
+
+        let head: Token<K> = Token::new(first, Span::new(span.offset(), offset));
+        let tail: Token<K> = Token::new(
+            second,
+            Span::new(span.offset() + offset, span.len() - offset),
+        );
+        self.tokens[self.pos] = head;
+        self.tokens.insert(self.pos + 1, tail);
+        self.edits.push(TokenEdit::Split {
+            pos: self.pos,
+            original,
+        });
+        self.skip_ignored();
+        true
+    }
+
+    /// Undoes the most recent token edit.
+    fn undo(&mut self) {
+        match self.edits.pop() {
+            Some(TokenEdit::Reclassify { pos, original }) => self.tokens[pos] = original,
+            Some(TokenEdit::Split { pos, original }) => {
+                self.tokens.remove(pos + 1);
+                self.tokens[pos] = original;
+            }
+            None => {}
+        }
+    }
+}
+
+impl<K: Copy + PartialEq + TokenKind> Parser<K> {
     //! Checkpoints
 
     /// Creates a checkpoint at the current position.
     pub fn checkpoint(&self) -> Checkpoint {
-        Checkpoint::new(self.pos, self.errors.len())
+        Checkpoint::new(self.pos, self.errors.len(), self.edits.len())
     }
 
-    /// Restores the parser to a previous checkpoint, rewinding position and discarding errors.
+    /// Restores the parser to a previous checkpoint, rewinding position, discarding errors and
+    /// undoing token edits made since.
     pub fn restore(&mut self, checkpoint: Checkpoint) {
+        while self.edits.len() > checkpoint.edit_count() {
+            self.undo();
+        }
         self.pos = checkpoint.pos();
         self.errors.truncate(checkpoint.error_count());
         self.skip_ignored();
@@ -235,6 +504,18 @@ impl<K: Copy> Parser<K> {
         self.errors.push(ParseError::new(span, message));
     }
 
+    /// Adds previously collected `errors`. (e.g. lexer diagnostics)
+    pub fn add_errors(&mut self, errors: impl IntoIterator<Item = ParseError>) {
+        self.errors.extend(errors);
+    }
+
+    /// Adds previously collected `errors`. (builder pattern)
+    #[must_use]
+    pub fn with_errors(mut self, errors: impl IntoIterator<Item = ParseError>) -> Self {
+        self.add_errors(errors);
+        self
+    }
+
     /// Gets the collected errors.
     pub fn errors(&self) -> &[ParseError] {
         &self.errors
@@ -0,0 +1,11 @@
+use crate::lexer::Token;
+
+/// A token stream edit made by the parser, recorded so checkpoints can undo it.
+#[derive(Copy, Clone, Debug)]
+pub(in crate::parser) enum TokenEdit<K> {
+    /// The token at `pos` was reclassified from `original`.
+    Reclassify { pos: usize, original: Token<K> },
+
+    /// The token at `pos` was split in two from `original`.
+    Split { pos: usize, original: Token<K> },
+}
@@ -1,7 +1,8 @@
-use lex::lexer::matchers::{digits, ident, whitespace};
-use lex::lexer::{Lexer, Token};
-use lex::parser::Parser;
-use lex::{lexer, line_comment, literal};
+use lex::lexer::decoders::{int, string};
+use lex::lexer::matchers::{Number, digits, ident, whitespace};
+use lex::lexer::{Lexer, Span, Token, Value};
+use lex::parser::{Checkpoint, Parser};
+use lex::{block_comment, keyword_ci, lexer, line_comment, literal};
 
 lexer! {
     #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -109,6 +110,37 @@ fn fn_parse_message() {
     );
 }
 
+#[test]
+fn fn_parse_message_lexer_errors() {
+    let source: String = "message Foo { string name = 1; }".to_string();
+    let lexer: Lexer<Kind> = Kind::lexer();
+    let (tokens, errors) = lexer.lex_diagnostics(&source);
+    let mut parser: Parser<Kind> = Parser::new(source, tokens)
+        .with_skip(Kind::Whitespace)
+        .with_errors(errors);
+
+    let message: Option<Message> = parse_message(&mut parser);
+
+    assert!(message.is_some());
+    assert!(parser.errors().is_empty());
+
+    let source: String = "message Foo { string @name = 1; }".to_string();
+    let (tokens, errors) = lexer.lex_diagnostics(&source);
+    let mut parser: Parser<Kind> = Parser::new(source, tokens)
+        .with_skip(Kind::Whitespace)
+        .with_errors(errors);
+
+    let message: Option<Message> = parse_message(&mut parser);
+
+    assert_eq!(message.map(|m| m.fields.len()), Some(0));
+    assert_eq!(parser.errors().len(), 2);
+    assert_eq!(parser.errors()[0].message(), "unexpected character '@'");
+    assert_eq!(
+        parser.errors()[1].message(),
+        "expected Ident, found Unrecognized"
+    );
+}
+
 #[test]
 fn fn_parse_message_empty() {
     let source: String = "message Empty {}".to_string();
@@ -325,3 +357,285 @@ fn fn_parse_message_no_comments() {
     assert!(message.comments.is_empty());
     assert!(message.fields[0].comments.is_empty());
 }
+
+lexer! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+    enum CommentKind {
+        Whitespace: whitespace,
+        DocComment: line_comment!("///"),
+        LineComment: line_comment!("//"),
+        BlockComment: block_comment!("/*", "*/", nested),
+        Ident: ident,
+    }
+}
+
+#[test]
+fn fn_parse_block_comments() {
+    let source: String = "/* one /* nested */ */\n// two\n/*three*/ name /**/ x".to_string();
+    let lexer: Lexer<CommentKind> = CommentKind::lexer();
+    let tokens: Vec<Token<CommentKind>> = lexer.lex(&source);
+    let mut parser: Parser<CommentKind> = Parser::new(source, tokens)
+        .with_skip(CommentKind::Whitespace)
+        .with_skip(CommentKind::LineComment)
+        .with_skip(CommentKind::BlockComment)
+        .with_line_comment(CommentKind::LineComment, "//")
+        .with_block_comment(CommentKind::BlockComment, "/*", "*/");
+
+    let comments: Vec<&str> = parser
+        .leading_comments()
+        .iter()
+        .map(|s| s.text(parser.source()))
+        .collect();
+    assert_eq!(comments, [" one /* nested */ ", " two", "three"]);
+
+    assert!(parser.expect(CommentKind::Ident).is_some());
+    let comments: Vec<&str> = parser
+        .leading_comments()
+        .iter()
+        .map(|s| s.text(parser.source()))
+        .collect();
+    assert_eq!(comments, [""]);
+}
+
+#[test]
+fn fn_parse_comment_configs() {
+    let source: String = "/// doc\n// line\nname".to_string();
+    let lexer: Lexer<CommentKind> = CommentKind::lexer();
+    let tokens: Vec<Token<CommentKind>> = lexer.lex(&source);
+    let mut parser: Parser<CommentKind> = Parser::new(source, tokens)
+        .with_skip(CommentKind::Whitespace)
+        .with_skip(CommentKind::DocComment)
+        .with_skip(CommentKind::LineComment)
+        .with_line_comment(CommentKind::DocComment, "///")
+        .with_line_comment(CommentKind::LineComment, "//");
+
+    let comments: Vec<&str> = parser
+        .leading_comments()
+        .iter()
+        .map(|s| s.text(parser.source()))
+        .collect();
+    assert_eq!(comments, [" line"]);
+
+    parser.add_line_comment(CommentKind::DocComment, "///");
+    let comments: Vec<&str> = parser
+        .leading_comments()
+        .iter()
+        .map(|s| s.text(parser.source()))
+        .collect();
+    assert_eq!(comments, [" doc", " line"]);
+}
+
+lexer! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+    enum ValueKind {
+        Whitespace: whitespace,
+        Int: digits => value(int),
+        Str: |s: &str| s.strip_prefix('"')?.find('"').map(|len| len + 2) => value(string),
+    }
+}
+
+#[test]
+fn fn_parse_values() {
+    let source: String = r#"42 "a\tb" 99999999999999999999999999999999999999999 "x\q""#.to_string();
+    let lexer: Lexer<ValueKind> = ValueKind::lexer();
+    let (tokens, values) = lexer.lex_values(&source);
+    let mut parser: Parser<ValueKind> = Parser::new(source, tokens)
+        .with_skip(ValueKind::Whitespace)
+        .with_values(values);
+
+    assert_eq!(parser.expect_value(ValueKind::Int), Some(Value::Int(42)));
+    assert_eq!(parser.expect_value(ValueKind::Int), None);
+    assert_eq!(parser.errors()[0].message(), "expected Int, found Str");
+    assert_eq!(
+        parser.expect_value(ValueKind::Str),
+        Some(Value::Str("a\tb".to_string()))
+    );
+    assert_eq!(parser.expect_value(ValueKind::Int), None);
+    assert_eq!(parser.errors()[1].message(), "invalid Int");
+    parser.advance();
+    assert_eq!(parser.expect_value(ValueKind::Str), None);
+    assert_eq!(parser.errors()[2].message(), "invalid Str");
+}
+
+lexer! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+    enum SqlKind {
+        Whitespace: whitespace,
+        Select: keyword_ci!("select"),
+        Ident: ident,
+        Star: literal!("*"),
+    }
+}
+
+#[test]
+fn fn_parse_ignore_case() {
+    let source: String = "SeLeCt * FROM t".to_string();
+    let lexer: Lexer<SqlKind> = SqlKind::lexer();
+    let tokens: Vec<Token<SqlKind>> = lexer.lex(&source);
+    let mut parser: Parser<SqlKind> = Parser::new(source, tokens).with_skip(SqlKind::Whitespace);
+
+    assert!(parser.expect(SqlKind::Select).is_some());
+    assert!(parser.expect(SqlKind::Star).is_some());
+    assert!(parser.check_ignore_case("from"));
+    assert_eq!(parser.accept_ignore_case(SqlKind::Ident, "where"), None);
+    assert!(parser.accept_ignore_case(SqlKind::Ident, "from").is_some());
+    assert!(parser.expect(SqlKind::Ident).is_some());
+    assert!(parser.errors().is_empty(), "errors: {:?}", parser.errors());
+}
+
+lexer! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+    enum NumberKind {
+        Whitespace: whitespace,
+        Int: Number::default().with_hexadecimal().with_separators().with_suffix("u8"),
+        Float: Number::default().with_floats(),
+    }
+}
+
+#[test]
+fn fn_parse_numbers() {
+    let source: String = "0xFF_u8 1_000 99999999999999999999 12 -".to_string();
+    let lexer: Lexer<NumberKind> = NumberKind::lexer();
+    let tokens: Vec<Token<NumberKind>> = lexer.lex(&source);
+    let mut parser: Parser<NumberKind> =
+        Parser::new(source, tokens).with_skip(NumberKind::Whitespace);
+
+    assert_eq!(parser.expect_u64(NumberKind::Int), Some(255));
+    assert_eq!(parser.expect_i128(NumberKind::Int), Some(1000));
+    assert_eq!(parser.expect_u64(NumberKind::Int), None);
+    assert_eq!(parser.errors()[0].span(), Span::new(14, 20));
+    assert_eq!(
+        parser.errors()[0].message(),
+        "integer literal out of range for u64"
+    );
+    assert_eq!(parser.expect_f64(NumberKind::Int), Some(12.0));
+    assert_eq!(parser.expect_f64(NumberKind::Float), None);
+    assert_eq!(
+        parser.errors()[1].message(),
+        "expected Float, found Unrecognized"
+    );
+    assert_eq!(parser.errors().len(), 2);
+}
+
+lexer! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+    enum SuffixKind {
+        Whitespace: whitespace,
+        Number: Number::default().with_trailing_dot().with_suffix("L").with_suffix("u8"),
+    }
+}
+
+#[test]
+fn fn_parse_number_suffixes() {
+    let source: String = "42L 1. 300u8 7L".to_string();
+    let lexer: Lexer<SuffixKind> = SuffixKind::lexer();
+    let tokens: Vec<Token<SuffixKind>> = lexer.lex(&source);
+    let mut parser: Parser<SuffixKind> = Parser::new(source, tokens)
+        .with_skip(SuffixKind::Whitespace)
+        .with_number_suffix("L");
+
+    assert_eq!(parser.expect_u64(SuffixKind::Number), Some(42));
+    assert_eq!(parser.expect_f64(SuffixKind::Number), Some(1.0));
+    assert_eq!(parser.expect_u64(SuffixKind::Number), None);
+    assert_eq!(parser.errors()[0].span(), Span::new(7, 5));
+    assert_eq!(
+        parser.errors()[0].message(),
+        "integer literal out of range for u64"
+    );
+    assert_eq!(parser.expect_i128(SuffixKind::Number), Some(7));
+    assert_eq!(parser.errors().len(), 1);
+}
+
+lexer! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+    enum GenericKind {
+        Whitespace: whitespace,
+        Ident: ident,
+        TypeName,
+        Shr: literal!(">>"),
+        Lt: literal!("<"),
+        Gt: literal!(">"),
+    }
+}
+
+#[test]
+fn fn_parse_token_edits() {
+    let source: String = "Vec<Vec<T>> x".to_string();
+    let lexer: Lexer<GenericKind> = GenericKind::lexer();
+    let tokens: Vec<Token<GenericKind>> = lexer.lex(&source);
+    let mut parser: Parser<GenericKind> =
+        Parser::new(source, tokens).with_skip(GenericKind::Whitespace);
+
+    let checkpoint: Checkpoint = parser.checkpoint();
+    assert!(parser.reclassify(GenericKind::TypeName));
+    assert!(parser.expect(GenericKind::TypeName).is_some());
+    assert!(parser.expect(GenericKind::Lt).is_some());
+    assert!(parser.expect(GenericKind::Ident).is_some());
+    assert!(parser.expect(GenericKind::Lt).is_some());
+    assert!(parser.expect(GenericKind::Ident).is_some());
+    assert!(parser.split(1, GenericKind::Gt, GenericKind::Gt));
+    assert_eq!(
+        parser.expect(GenericKind::Gt),
+        Some(Token::new(GenericKind::Gt, Span::new(9, 1)))
+    );
+    assert_eq!(
+        parser.expect(GenericKind::Gt),
+        Some(Token::new(GenericKind::Gt, Span::new(10, 1)))
+    );
+    assert!(parser.expect(GenericKind::Ident).is_some());
+
+    parser.restore(checkpoint);
+    assert!(parser.expect(GenericKind::Ident).is_some());
+    assert!(parser.expect(GenericKind::Lt).is_some());
+    assert!(parser.expect(GenericKind::Ident).is_some());
+    assert!(parser.expect(GenericKind::Lt).is_some());
+    assert!(parser.expect(GenericKind::Ident).is_some());
+    assert_eq!(
+        parser.expect(GenericKind::Shr),
+        Some(Token::new(GenericKind::Shr, Span::new(9, 2)))
+    );
+    assert!(parser.expect(GenericKind::Ident).is_some());
+    assert!(parser.errors().is_empty(), "errors: {:?}", parser.errors());
+}
+
+#[test]
+fn fn_parse_token_edits_rejected() {
+    let source: String = ">> é".to_string();
+    let lexer: Lexer<GenericKind> = GenericKind::lexer();
+    let tokens: Vec<Token<GenericKind>> = lexer.lex(&source);
+    let mut parser: Parser<GenericKind> =
+        Parser::new(source, tokens).with_skip(GenericKind::Whitespace);
+
+    assert!(!parser.split(0, GenericKind::Gt, GenericKind::Gt));
+    assert!(!parser.split(2, GenericKind::Gt, GenericKind::Gt));
+    assert!(!parser.split(3, GenericKind::Gt, GenericKind::Gt));
+    assert!(parser.expect(GenericKind::Shr).is_some());
+    assert_eq!(parser.peek().span(), Span::new(3, 2));
+    assert!(!parser.split(1, GenericKind::Gt, GenericKind::Gt));
+    assert!(parser.expect(GenericKind::Unrecognized).is_some());
+    assert!(!parser.split(1, GenericKind::Gt, GenericKind::Gt));
+    assert!(!parser.reclassify(GenericKind::Gt));
+    assert_eq!(parser.peek().kind(), GenericKind::EndOfFile);
+    assert!(parser.errors().is_empty(), "errors: {:?}", parser.errors());
+}
+
+#[test]
+fn fn_parse_token_edits_values() {
+    let source: String = "42 7".to_string();
+    let lexer: Lexer<ValueKind> = ValueKind::lexer();
+    let (tokens, values) = lexer.lex_values(&source);
+    let mut parser: Parser<ValueKind> = Parser::new(source, tokens)
+        .with_skip(ValueKind::Whitespace)
+        .with_values(values);
+
+    let checkpoint: Checkpoint = parser.checkpoint();
+    assert!(parser.split(1, ValueKind::Int, ValueKind::Int));
+    assert_eq!(parser.value(parser.peek()), None);
+    assert_eq!(parser.expect_value(ValueKind::Int), None);
+    assert_eq!(parser.errors()[0].message(), "invalid Int");
+
+    parser.restore(checkpoint);
+    assert_eq!(parser.expect_value(ValueKind::Int), Some(Value::Int(42)));
+    assert_eq!(parser.expect_value(ValueKind::Int), Some(Value::Int(7)));
+    assert!(parser.errors().is_empty(), "errors: {:?}", parser.errors());
+}